@@ -0,0 +1,8 @@
+#![no_main]
+#![no_std]
+
+#[cortex_m_rt::entry]
+fn main() -> ! {
+    let bytes = include_bytes!("../max.gcode");
+    gcode_bench_stm32g0::bench_main_bytes(bytes)
+}
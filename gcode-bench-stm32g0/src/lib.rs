@@ -5,12 +5,24 @@ use defmt_rtt as _;
 use panic_probe as _;
 
 use defmt::{println, Format};
-use gcode::{BlockBuilder, BlockParser, Decimal};
+use gcode::{BlockBuilder, BlockParser, Decimal, Error};
 use pac::gpio::vals::{Moder, Ospeedr, Ot, Pupdr};
 use stm32_metapac as pac;
 
 #[inline(never)]
 pub fn bench_main(s: &str) -> ! {
+    bench(|parser, builder| parser.try_feed_str(s, builder))
+}
+
+#[inline(never)]
+pub fn bench_main_bytes(bytes: &[u8]) -> ! {
+    bench(|parser, builder| parser.try_feed_bytes(bytes, builder))
+}
+
+fn bench<F>(mut feed: F) -> !
+where
+    F: FnMut(&mut BlockParser<i32>, &mut NoOpBlockBuilder) -> Result<(), Error<()>>,
+{
     let pin = 0;
 
     unsafe {
@@ -52,7 +64,7 @@ pub fn bench_main(s: &str) -> ! {
                 pac::GPIOA.bsrr().write(|w| w.set_br(pin, true));
             }
         };
-        if let Err(e) = parser.try_feed_str(s, &mut builder) {
+        if let Err(e) = feed(&mut parser, &mut builder) {
             println!("{:?}", e);
             break;
         };
@@ -79,7 +91,7 @@ impl BlockBuilder for NoOpBlockBuilder {
     fn sequence_number(
         &mut self,
         _alignment: bool,
-        _number: Decimal<Self::Significand>,
+        _number: Decimal<Self::SequenceNumber>,
     ) -> Result<(), Self::Error> {
         Ok(())
     }
@@ -98,7 +110,7 @@ impl BlockBuilder for NoOpBlockBuilder {
     fn data(
         &mut self,
         _address: char,
-        _index: Option<Self::Significand>,
+        _index: Option<Self::Index>,
         _number: Decimal<Self::Significand>,
     ) -> Result<(), Self::Error> {
         Ok(())
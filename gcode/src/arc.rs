@@ -0,0 +1,923 @@
+use crate::decimal::Decimal;
+use crate::digits::Digits;
+use crate::interpreter::{FeedRateMode, MachineState};
+use crate::machine::{Arc, Machine, Plane, Point, AXES};
+use crate::significand::Significand;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// The number of extra decimal digits of the fixed-point computations.
+const GUARD: u32 = 3;
+
+/// The number of fractional bits of angles, sines, and cosines.
+const FRACTION: u32 = 30;
+
+/// One, with [`FRACTION`] fractional bits.
+const ONE: i64 = 1 << FRACTION;
+
+/// π radians, with [`FRACTION`] fractional bits.
+const PI: i64 = 3_373_259_426;
+
+/// The arctangents of `2`<sup>`-i`</sup>, in radians, with [`FRACTION`] fractional bits.
+const ATAN: [i64; 30] = [
+    843_314_857,
+    497_837_829,
+    263_043_837,
+    133_525_159,
+    67_021_687,
+    33_543_516,
+    16_775_851,
+    8_388_437,
+    4_194_283,
+    2_097_149,
+    1_048_576,
+    524_288,
+    262_144,
+    131_072,
+    65_536,
+    32_768,
+    16_384,
+    8_192,
+    4_096,
+    2_048,
+    1_024,
+    512,
+    256,
+    128,
+    64,
+    32,
+    16,
+    8,
+    4,
+    2,
+];
+
+/// The inverse of the gain of the CORDIC iterations, with [`FRACTION`] fractional bits.
+const GAIN: i64 = 652_032_874;
+
+/// The error type for arcs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum ArcError {
+    /// The tolerance is zero or negative.
+    InvalidTolerance,
+
+    /// The radius is zero, or too small to reach from the start to the end.
+    InvalidRadius,
+
+    /// The distances from the center to the start and to the end differ by more than the
+    /// tolerance.
+    EndpointMismatch,
+
+    /// A computation exceeded the bounds of the [`Significand`] numeric type.
+    Overflow,
+}
+
+/// An iterator over the ends of the straight segments that approximate an [`Arc`].
+///
+/// No point of a segment is farther from the arc than the tolerance. The segments have equal
+/// angles, and a helical move rises by equal steps along the axis normal to the plane. The last
+/// point is exactly the end of the arc, and the others have as many digits right of the decimal
+/// point as the most precise of the arc and the tolerance. The tolerance includes the rounding of
+/// the points to those digits, so a tolerance with more digits than the arc (e.g., `0.010`
+/// instead of `0.01`) needs fewer segments.
+///
+/// The center of an arc with a radius (`R` word) instead of offsets can be computed with
+/// [`radius_center`].
+///
+/// All computations use integer and fixed-point arithmetic (including a CORDIC implementation of
+/// sines, cosines, and arctangents), so they are suitable for targets without a floating-point
+/// unit.
+///
+///     use gcode::{Arc, ArcSegments, Decimal, Plane, Point};
+///
+///     let point = |x, y| Point::new(Decimal::new(x, 0), Decimal::new(y, 0), Decimal::new(0, 0));
+///     let arc = Arc::new(point(10, 0), point(-10, 0), point(0, 0), Plane::XY, false);
+///     let segments = ArcSegments::new(arc, Decimal::new(1, 0)).unwrap();
+///     let points = [
+///         point(9, 4),
+///         point(6, 8),
+///         point(2, 10),
+///         point(-2, 10),
+///         point(-6, 8),
+///         point(-9, 4),
+///         point(-10, 0),
+///     ];
+///     assert_eq!(segments.collect::<Vec<_>>(), points);
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct ArcSegments<S>
+where
+    S: Significand,
+{
+    end: Point<S>,
+    axes: (usize, usize, usize),
+    center: (i64, i64),
+    radius: i64,
+    start_angle: i64,
+    sweep: i64,
+    start_normal: i64,
+    end_normal: i64,
+    negative_exponent: u32,
+    count: u32,
+    index: u32,
+}
+
+impl<S> ArcSegments<S>
+where
+    S: Significand,
+{
+    /// Creates a new [`ArcSegments`] iterator over the segments of `arc`, within `tolerance`.
+    ///
+    /// The distances from the center to the start and to the end of the arc must not differ by
+    /// more than the tolerance. If the start and the end are at the same angle, the arc is a full
+    /// circle.
+    pub fn new(arc: Arc<S>, tolerance: Decimal<S>) -> Result<Self, ArcError> {
+        if tolerance.significand().is_negative() || tolerance.significand().is_zero() {
+            return Err(ArcError::InvalidTolerance);
+        }
+        let (first, second, normal) = arc.plane().axes();
+        let (start, end, center) = (arc.start(), arc.end(), arc.center());
+        let negative_exponent = [start, end, center]
+            .iter()
+            .flat_map(|point| (0..AXES).map(|axis| point.axis(axis).negative_exponent()))
+            .fold(tolerance.negative_exponent(), u32::max);
+        let fixed = |value| to_fixed(value, negative_exponent).ok_or(ArcError::Overflow);
+        let tolerance = fixed(tolerance)?;
+        let center_point = (fixed(center.axis(first))?, fixed(center.axis(second))?);
+        let radius_of = |point: Point<S>| -> Result<(i64, i64, i64), ArcError> {
+            let x = fixed(point.axis(first))?
+                .checked_sub(center_point.0)
+                .ok_or(ArcError::Overflow)?;
+            let y = fixed(point.axis(second))?
+                .checked_sub(center_point.1)
+                .ok_or(ArcError::Overflow)?;
+            let radius = hypot(x, y).ok_or(ArcError::Overflow)?;
+            Ok((x, y, radius))
+        };
+        let (start_x, start_y, radius) = radius_of(start)?;
+        let (end_x, end_y, end_radius) = radius_of(end)?;
+        if 0 == radius || 0 == end_radius {
+            return Err(ArcError::InvalidRadius);
+        }
+        if radius.abs_diff(end_radius) > tolerance.unsigned_abs() {
+            return Err(ArcError::EndpointMismatch);
+        }
+        let start_angle = atan2(start_y, start_x);
+        let end_angle = atan2(end_y, end_x);
+        let sweep = if arc.is_clockwise() {
+            start_angle - end_angle
+        } else {
+            end_angle - start_angle
+        };
+        let sweep = if sweep <= 0 { sweep + 2 * PI } else { sweep };
+        // Rounding a point to the digits of the output moves it by up to half a digit along each
+        // axis of the plane (i.e., √2 / 2 of a digit), and the sines and cosines are off by up to
+        // 2^-26 of the radius. The segments get the rest of the tolerance.
+        let rounding = 70_711 * 10_i64.pow(GUARD) / 100_000 + (radius >> 26) + 1;
+        let chord_tolerance = tolerance.saturating_sub(rounding).max(1);
+        let count = sweep
+            .unsigned_abs()
+            .div_ceil(max_angle(radius, chord_tolerance).unsigned_abs());
+        let count = u32::try_from(count).map_err(|_| ArcError::Overflow)?;
+        let segments = Self {
+            end,
+            axes: (first, second, normal),
+            center: center_point,
+            radius,
+            start_angle,
+            sweep: if arc.is_clockwise() { -sweep } else { sweep },
+            start_normal: fixed(start.axis(normal))?,
+            end_normal: fixed(end.axis(normal))?,
+            negative_exponent,
+            count,
+            index: 0,
+        };
+        // Every point is within the bounding box of the circle and the ends along the normal.
+        let bounds = [
+            center_point.0.checked_sub(radius),
+            center_point.0.checked_add(radius),
+            center_point.1.checked_sub(radius),
+            center_point.1.checked_add(radius),
+            Some(segments.start_normal),
+            Some(segments.end_normal),
+        ];
+        for bound in bounds {
+            bound
+                .and_then(|bound| from_fixed::<S>(bound, negative_exponent))
+                .ok_or(ArcError::Overflow)?;
+        }
+        Ok(segments)
+    }
+
+    fn point(&self, index: u32) -> Option<Point<S>> {
+        let (first, second, normal) = self.axes;
+        let fraction = |value: i64| {
+            i64::try_from(i128::from(value) * i128::from(index) / i128::from(self.count)).ok()
+        };
+        let (cos, sin) = cos_sin(self.start_angle + fraction(self.sweep)?);
+        let along = |cos: i64| {
+            let offset = i128::from(self.radius) * i128::from(cos);
+            i64::try_from(shift_round(offset, FRACTION)).ok()
+        };
+        let x = self.center.0.checked_add(along(cos)?)?;
+        let y = self.center.1.checked_add(along(sin)?)?;
+        let z = self
+            .start_normal
+            .checked_add(fraction(self.end_normal.checked_sub(self.start_normal)?)?)?;
+        let mut point = Point::default();
+        point.set_axis(first, from_fixed(x, self.negative_exponent)?);
+        point.set_axis(second, from_fixed(y, self.negative_exponent)?);
+        point.set_axis(normal, from_fixed(z, self.negative_exponent)?);
+        Some(point)
+    }
+}
+
+impl<S> Iterator for ArcSegments<S>
+where
+    S: Significand,
+{
+    type Item = Point<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+        self.index += 1;
+        if self.index == self.count {
+            Some(self.end)
+        } else {
+            self.point(self.index)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = usize::try_from(self.count - self.index).unwrap_or(usize::MAX);
+        (len, Some(len))
+    }
+}
+
+impl<S> ExactSizeIterator for ArcSegments<S> where S: Significand {}
+
+/// The error type for the [`Linearizer`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum LinearizerError<E> {
+    /// The inner [`Machine`] produced an error.
+    MachineError(E),
+
+    /// The arc is invalid.
+    ArcError(ArcError),
+}
+
+impl<E> Eq for LinearizerError<E> where E: Eq {}
+
+/// A [`Machine`] wrapper that replaces each arc with straight moves.
+///
+/// The [`Linearizer`] passes every call to the inner [`Machine`], except
+/// [`Machine::arc`], which it replaces with calls to [`Machine::linear`] for the
+/// [`ArcSegments`] of the arc. In inverse time mode (`G93`), it multiplies the feed rate by the
+/// number of segments, so the whole arc still takes the same time. The
+/// [`Machine::arc`] method of the inner [`Machine`] is never called.
+///
+///     use gcode::{BlockParser, Decimal, Interpreter, Linearizer};
+///     # use gcode::{Arc, Machine, Point};
+///     # struct Lines(usize);
+///     # impl Machine for Lines {
+///     #     type Error = ();
+///     #     type Significand = i32;
+///     #     fn rapid(&mut self, end: Point<i32>) -> Result<(), ()> { Ok(()) }
+///     #     fn linear(&mut self, end: Point<i32>, feed_rate: Decimal<i32>) -> Result<(), ()> {
+///     #         self.0 += 1;
+///     #         Ok(())
+///     #     }
+///     #     fn arc(&mut self, arc: Arc<i32>, feed_rate: Decimal<i32>) -> Result<(), ()> {
+///     #         Err(())
+///     #     }
+///     # }
+///
+///     let machine = Linearizer::new(Lines(0), Decimal::new(10, 3));
+///     let mut interpreter = Interpreter::new(machine);
+///     let mut parser = BlockParser::<i32>::default();
+///     parser.try_feed_str("G0 X10\nG2 X-10 R10 F100\n", &mut interpreter).unwrap();
+///     assert_eq!(interpreter.machine().machine().0, 37);
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Linearizer<M>
+where
+    M: Machine,
+{
+    machine: M,
+    tolerance: Decimal<M::Significand>,
+    inverse_time: bool,
+}
+
+impl<M> Linearizer<M>
+where
+    M: Machine,
+{
+    /// Creates a new [`Linearizer`] that approximates arcs within `tolerance` (in millimeters.)
+    pub const fn new(machine: M, tolerance: Decimal<M::Significand>) -> Self {
+        Self {
+            machine,
+            tolerance,
+            inverse_time: false,
+        }
+    }
+
+    /// Returns a reference to the inner machine.
+    pub const fn machine(&self) -> &M {
+        &self.machine
+    }
+
+    /// Returns a mutable reference to the inner machine.
+    pub const fn machine_mut(&mut self) -> &mut M {
+        &mut self.machine
+    }
+
+    /// Returns the inner machine.
+    pub fn into_machine(self) -> M {
+        self.machine
+    }
+
+    /// Returns the tolerance.
+    pub const fn tolerance(&self) -> Decimal<M::Significand> {
+        self.tolerance
+    }
+}
+
+impl<M> Machine for Linearizer<M>
+where
+    M: Machine,
+{
+    type Error = LinearizerError<M::Error>;
+    type Significand = M::Significand;
+
+    fn rapid(&mut self, end: Point<Self::Significand>) -> Result<(), Self::Error> {
+        self.machine
+            .rapid(end)
+            .map_err(LinearizerError::MachineError)
+    }
+
+    fn linear(
+        &mut self,
+        end: Point<Self::Significand>,
+        feed_rate: Decimal<Self::Significand>,
+    ) -> Result<(), Self::Error> {
+        self.machine
+            .linear(end, feed_rate)
+            .map_err(LinearizerError::MachineError)
+    }
+
+    fn arc(
+        &mut self,
+        arc: Arc<Self::Significand>,
+        feed_rate: Decimal<Self::Significand>,
+    ) -> Result<(), Self::Error> {
+        let segments = ArcSegments::new(arc, self.tolerance).map_err(LinearizerError::ArcError)?;
+        let feed_rate = if self.inverse_time {
+            u32::try_from(segments.len())
+                .ok()
+                .and_then(|count| Self::Significand::default().checked_add_unsigned(count))
+                .and_then(|count| feed_rate.checked_mul(Decimal::new(count, 0)))
+                .ok_or(LinearizerError::ArcError(ArcError::Overflow))?
+        } else {
+            feed_rate
+        };
+        for end in segments {
+            self.linear(end, feed_rate)?;
+        }
+        Ok(())
+    }
+
+    fn dwell(&mut self, seconds: Decimal<Self::Significand>) -> Result<(), Self::Error> {
+        self.machine
+            .dwell(seconds)
+            .map_err(LinearizerError::MachineError)
+    }
+
+    fn select_tool(&mut self, tool: Decimal<Self::Significand>) -> Result<(), Self::Error> {
+        self.machine
+            .select_tool(tool)
+            .map_err(LinearizerError::MachineError)
+    }
+
+    fn m_code(&mut self, number: Decimal<Self::Significand>) -> Result<(), Self::Error> {
+        self.machine
+            .m_code(number)
+            .map_err(LinearizerError::MachineError)
+    }
+
+    fn modal_state(&mut self, state: &MachineState<Self::Significand>) -> Result<(), Self::Error> {
+        self.inverse_time = FeedRateMode::InverseTime == state.feed_rate_mode();
+        self.machine
+            .modal_state(state)
+            .map_err(LinearizerError::MachineError)
+    }
+}
+
+/// Returns the center of an arc with the specified radius (`R` word.)
+///
+/// A positive radius selects the arc of at most a half circle, and a negative radius selects the
+/// arc of at least a half circle. The center has the same normal coordinate as `start`.
+///
+///     use gcode::{radius_center, Decimal, Plane, Point};
+///
+///     let point = |x, y| Point::new(Decimal::new(x, 0), Decimal::new(y, 0), Decimal::new(0, 0));
+///     let center = radius_center(point(0, 0), point(10, 0), Decimal::new(5, 0), Plane::XY, true);
+///     assert_eq!(center, Ok(point(5, 0)));
+pub fn radius_center<S>(
+    start: Point<S>,
+    end: Point<S>,
+    radius: Decimal<S>,
+    plane: Plane,
+    clockwise: bool,
+) -> Result<Point<S>, ArcError>
+where
+    S: Significand,
+{
+    let (first, second, _) = plane.axes();
+    let negative_exponent = [first, second]
+        .iter()
+        .flat_map(|&axis| [start.axis(axis), end.axis(axis)])
+        .map(|value| value.negative_exponent())
+        .fold(radius.negative_exponent(), u32::max);
+    let fixed = |value| to_fixed(value, negative_exponent).ok_or(ArcError::Overflow);
+    let (start_x, start_y) = (fixed(start.axis(first))?, fixed(start.axis(second))?);
+    let (end_x, end_y) = (fixed(end.axis(first))?, fixed(end.axis(second))?);
+    let radius = fixed(radius)?;
+    let dx = i128::from(end_x) - i128::from(start_x);
+    let dy = i128::from(end_y) - i128::from(start_y);
+    if 0 == radius || (0 == dx && 0 == dy) {
+        return Err(ArcError::InvalidRadius);
+    }
+    let chord_squared = dx
+        .checked_mul(dx)
+        .and_then(|x| x.checked_add(dy.checked_mul(dy)?))
+        .ok_or(ArcError::Overflow)?;
+    let chord = chord_squared.unsigned_abs().isqrt();
+    let diameter = 2 * u128::from(radius.unsigned_abs());
+    // The height of the center above the chord, doubled. A chord slightly longer than the
+    // diameter (by at most one digit of the input) is a rounded half circle.
+    let height = match diameter
+        .checked_mul(diameter)
+        .and_then(|d| d.checked_sub(chord_squared.unsigned_abs()))
+    {
+        Some(squared) => squared.isqrt(),
+        None if chord - diameter <= 10_u128.pow(GUARD) => 0,
+        None => return Err(ArcError::InvalidRadius),
+    };
+    let height = i128::try_from(height).map_err(|_| ArcError::Overflow)?;
+    let chord = i128::try_from(chord).map_err(|_| ArcError::Overflow)?;
+    // The center is right of the chord for a clockwise arc of at most a half circle.
+    let side = if clockwise == (radius > 0) { 1 } else { -1 };
+    let center = |start: i64, end: i64, perpendicular: i128| {
+        let offset = height.checked_mul(perpendicular)? / chord * side;
+        let twice = (i128::from(start) + i128::from(end)).checked_add(offset)?;
+        from_fixed(i64::try_from(twice / 2).ok()?, negative_exponent)
+    };
+    let mut point = start;
+    point.set_axis(first, center(start_x, end_x, dy).ok_or(ArcError::Overflow)?);
+    point.set_axis(
+        second,
+        center(start_y, end_y, -dx).ok_or(ArcError::Overflow)?,
+    );
+    Ok(point)
+}
+
+/// Returns the maximum angle of a segment of a circle of `radius`, with [`FRACTION`] fractional
+/// bits, such that the segment is never farther from the circle than `tolerance`.
+fn max_angle(radius: i64, tolerance: i64) -> i64 {
+    // The distance from the middle of the segment to the circle is r (1 - cos(θ / 2)).
+    let ratio = (i128::from(tolerance) << FRACTION) / i128::from(radius);
+    let Ok(cos) = i64::try_from(i128::from(ONE) - ratio) else {
+        return PI;
+    };
+    if cos <= 0 {
+        return PI;
+    }
+    let (one, magnitude) = (ONE.unsigned_abs(), cos.unsigned_abs());
+    let sin_squared = u128::from(one) * u128::from(one) - u128::from(magnitude).pow(2);
+    let sin = i64::try_from(sin_squared.isqrt()).unwrap_or(ONE);
+    (2 * atan2(sin, cos)).max(1)
+}
+
+/// Returns the length of the vector `(x, y)`, rounded down.
+fn hypot(x: i64, y: i64) -> Option<i64> {
+    let x = i128::from(x);
+    let y = i128::from(y);
+    let squared = (x * x).checked_add(y * y)?;
+    i64::try_from(squared.unsigned_abs().isqrt()).ok()
+}
+
+/// Returns the cosine and the sine of an angle in radians, with [`FRACTION`] fractional bits.
+fn cos_sin(angle: i64) -> (i64, i64) {
+    let mut angle = angle.rem_euclid(2 * PI);
+    if angle > PI {
+        angle -= 2 * PI;
+    }
+    // The iterations converge for angles of up to about ±1.74 radians.
+    let negate = angle.abs() > PI / 2;
+    if negate {
+        angle -= PI * angle.signum();
+    }
+    let (mut x, mut y) = (GAIN, 0_i64);
+    for (i, atan) in ATAN.iter().enumerate() {
+        let (dx, dy) = (y >> i, x >> i);
+        if angle >= 0 {
+            (x, y) = (x - dx, y + dy);
+            angle -= atan;
+        } else {
+            (x, y) = (x + dx, y - dy);
+            angle += atan;
+        }
+    }
+    if negate {
+        (-x, -y)
+    } else {
+        (x, y)
+    }
+}
+
+/// Returns the angle of the vector `(x, y)` in radians, from -π to π, with [`FRACTION`] fractional
+/// bits.
+fn atan2(y: i64, x: i64) -> i64 {
+    let magnitude = x.unsigned_abs().max(y.unsigned_abs());
+    if 0 == magnitude {
+        return 0;
+    }
+    // Scale the vector to 40 bits, which is precise enough and cannot overflow.
+    let shift = i64::from(magnitude.leading_zeros()) - 24;
+    let (x, y) = if shift >= 0 {
+        (x << shift, y << shift)
+    } else {
+        (x >> -shift, y >> -shift)
+    };
+    // Turn the vector by half a turn into the right half-plane, where the iterations converge.
+    let (mut x, mut y, mut angle) = match (x < 0, y < 0) {
+        (false, _) => (x, y, 0),
+        (true, false) => (-x, -y, PI),
+        (true, true) => (-x, -y, -PI),
+    };
+    for (i, atan) in ATAN.iter().enumerate() {
+        let (dx, dy) = (y >> i, x >> i);
+        if y > 0 {
+            (x, y) = (x + dx, y - dy);
+            angle += atan;
+        } else {
+            (x, y) = (x - dx, y + dy);
+            angle -= atan;
+        }
+    }
+    angle
+}
+
+/// Divides by `2`<sup>`shift`</sup>, rounding half away from zero.
+const fn shift_round(value: i128, shift: u32) -> i128 {
+    let half = 1 << (shift - 1);
+    if value < 0 {
+        -((half - value) >> shift)
+    } else {
+        (value + half) >> shift
+    }
+}
+
+/// Converts a [`Decimal`] number to an integer count of `10`<sup>`-negative_exponent`</sup>
+/// units, with [`GUARD`] extra digits. Returns `None` if overflow occurred.
+fn to_fixed<S>(value: Decimal<S>, negative_exponent: u32) -> Option<i64>
+where
+    S: Significand,
+{
+    let magnitude: i64 = Digits::new(value.significand()).as_str().parse().ok()?;
+    let exp = (negative_exponent + GUARD).checked_sub(value.negative_exponent())?;
+    let magnitude = magnitude.checked_mul(10_i64.checked_pow(exp)?)?;
+    Some(if value.significand().is_negative() {
+        -magnitude
+    } else {
+        magnitude
+    })
+}
+
+/// Converts the result of [`to_fixed`] back to a [`Decimal`] number, rounding half away from
+/// zero. Returns `None` if overflow occurred.
+fn from_fixed<S>(value: i64, negative_exponent: u32) -> Option<Decimal<S>>
+where
+    S: Significand,
+{
+    let divisor = 10_i64.pow(GUARD);
+    let (quotient, remainder) = (value / divisor, value % divisor);
+    let rounded = if 2 * remainder.abs() >= divisor {
+        quotient + value.signum()
+    } else {
+        quotient
+    };
+    let mut significand = S::default();
+    for digit in Digits::new(rounded).as_str().bytes() {
+        let digit = u32::from(digit - b'0');
+        significand = significand.checked_shl10(1)?;
+        significand = if rounded < 0 {
+            significand.checked_sub_unsigned(digit)?
+        } else {
+            significand.checked_add_unsigned(digit)?
+        };
+    }
+    Some(Decimal::new(significand, negative_exponent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::decimal::Rounding;
+    use crate::interpreter::InterpreterError;
+    use crate::testing::{error, interpret, point, Move};
+
+    extern crate std;
+    use std::vec::Vec;
+
+    fn segments(arc: Arc<i32>, tolerance: Decimal<i32>) -> Vec<Point<i32>> {
+        ArcSegments::new(arc, tolerance).unwrap().collect()
+    }
+
+    #[test]
+    fn cos_sin_angles() {
+        let close = |(cos, sin): (i64, i64), (x, y): (i64, i64)| {
+            (cos - x).abs() < 16 && (sin - y).abs() < 16
+        };
+        assert!(close(cos_sin(0), (ONE, 0)));
+        assert!(close(cos_sin(PI / 2), (0, ONE)));
+        assert!(close(cos_sin(PI), (-ONE, 0)));
+        assert!(close(cos_sin(-PI / 2), (0, -ONE)));
+        assert!(close(cos_sin(5 * PI / 2), (0, ONE)));
+    }
+
+    #[test]
+    fn atan2_angles() {
+        let close = |angle: i64, expected: i64| (angle - expected).abs() < 16;
+        assert!(close(atan2(0, 1), 0));
+        assert!(close(atan2(1, 1), PI / 4));
+        assert!(close(atan2(1_000_000, 0), PI / 2));
+        assert!(close(atan2(0, -3).abs(), PI));
+        assert!(close(atan2(-1, -1), -3 * PI / 4));
+        assert!(close(atan2(-i64::MAX, 0), -PI / 2));
+    }
+
+    #[test]
+    fn arc_segments_full_circle() {
+        let arc = Arc::new(
+            point(10, 0, 0),
+            point(10, 0, 0),
+            point(0, 0, 0),
+            Plane::XY,
+            false,
+        );
+        assert_eq!(
+            segments(arc, Decimal::new(4, 0)),
+            [
+                point(0, 10, 0),
+                point(-10, 0, 0),
+                point(0, -10, 0),
+                point(10, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn arc_segments_helix() {
+        let arc = Arc::new(
+            point(10, 0, 0),
+            point(0, -10, 4),
+            point(0, 0, 0),
+            Plane::XY,
+            true,
+        );
+        let middle = Point::new(
+            Decimal::new(7071, 3),
+            Decimal::new(-7071, 3),
+            Decimal::new(2, 0),
+        );
+        assert_eq!(
+            segments(arc, Decimal::new(1000, 3)),
+            [middle, point(0, -10, 4)]
+        );
+    }
+
+    #[test]
+    fn arc_segments_plane() {
+        let arc = Arc::new(
+            point(0, 0, 10),
+            point(10, 0, 0),
+            point(0, 0, 0),
+            Plane::ZX,
+            false,
+        );
+        let segments = ArcSegments::new(arc, Decimal::new(1, 2)).unwrap();
+        assert_eq!(segments.len(), 33);
+        for point in segments {
+            let x = point.x().to_scaled(2, Rounding::Truncate).unwrap().value();
+            let z = point.z().to_scaled(2, Rounding::Truncate).unwrap().value();
+            assert!((0..=1_000).contains(&x));
+            assert!((0..=1_000).contains(&z));
+            assert_eq!(point.y(), Decimal::new(0, 0));
+            let radius = hypot(i64::from(x), i64::from(z)).unwrap();
+            assert!((999..=1_000).contains(&radius));
+        }
+    }
+
+    #[test]
+    fn arc_segments_tolerance() {
+        let arc = Arc::new(
+            point(10, 0, 0),
+            point(0, 10, 0),
+            point(0, 0, 0),
+            Plane::XY,
+            false,
+        );
+        let count = |tolerance| ArcSegments::new(arc, tolerance).unwrap().len();
+        assert_eq!(count(Decimal::new(10, 0)), 1);
+        assert_eq!(count(Decimal::new(1, 0)), 4);
+        assert_eq!(count(Decimal::new(1, 2)), 33);
+        assert_eq!(count(Decimal::new(10, 3)), 19);
+        assert_eq!(count(Decimal::new(1, 4)), 326);
+    }
+
+    #[test]
+    fn arc_segments_deviation() {
+        let float = |value: Decimal<i32>| {
+            let exponent = i32::try_from(value.negative_exponent()).unwrap();
+            f64::from(value.significand()) / 10_f64.powi(exponent)
+        };
+        // The points of a half circle are rounded to whole numbers, by up to half of the tolerance
+        // along each axis.
+        for radius in 1..=400 {
+            let arc = Arc::new(
+                point(radius, 0, 0),
+                point(-radius, 0, 0),
+                point(0, 0, 0),
+                Plane::XY,
+                false,
+            );
+            let radius = f64::from(radius);
+            let mut start = (radius, 0.0);
+            for end in segments(arc, Decimal::new(1, 0)) {
+                let end = (float(end.x()), float(end.y()));
+                let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+                let along = -(start.0 * dx + start.1 * dy) / (dx * dx + dy * dy);
+                let nearest = along.clamp(0.0, 1.0);
+                let inside = (start.0 + nearest * dx).hypot(start.1 + nearest * dy);
+                let outside = end.0.hypot(end.1);
+                let deviation = (radius - inside).max(outside - radius);
+                assert!(deviation <= 1.0, "{radius} {deviation}");
+                start = end;
+            }
+        }
+    }
+
+    #[test]
+    fn arc_segments_errors() {
+        let error = |arc, tolerance| ArcSegments::new(arc, tolerance).unwrap_err();
+        let arc = Arc::new(
+            point(10, 0, 0),
+            point(0, 12, 0),
+            point(0, 0, 0),
+            Plane::XY,
+            false,
+        );
+        assert_eq!(error(arc, Decimal::new(1, 0)), ArcError::EndpointMismatch);
+        assert!(ArcSegments::new(arc, Decimal::new(2, 0)).is_ok());
+        assert_eq!(error(arc, Decimal::new(0, 0)), ArcError::InvalidTolerance);
+        assert_eq!(error(arc, Decimal::new(-1, 0)), ArcError::InvalidTolerance);
+        let arc = Arc::new(
+            point(0, 0, 0),
+            point(0, 0, 0),
+            point(0, 0, 0),
+            Plane::XY,
+            false,
+        );
+        assert_eq!(error(arc, Decimal::new(1, 0)), ArcError::InvalidRadius);
+    }
+
+    #[test]
+    fn arc_segments_overflow() {
+        let point = |x, y| Point::new(Decimal::new(x, 0), Decimal::new(y, 0), Decimal::new(0, 0));
+        let arc = Arc::new(
+            point(100, 100),
+            point(-100, 100),
+            point(0, 0),
+            Plane::XY,
+            false,
+        );
+        let result = ArcSegments::<i8>::new(arc, Decimal::new(1, 0));
+        assert_eq!(result.unwrap_err(), ArcError::Overflow);
+        let arc = Arc::new(point(90, 0), point(0, 90), point(0, 0), Plane::XY, false);
+        assert!(ArcSegments::<i8>::new(arc, Decimal::new(1, 0)).is_ok());
+    }
+
+    fn linearize(s: &str) -> Vec<Move> {
+        let machine = Linearizer::new(Vec::new(), Decimal::new(4, 0));
+        interpret(machine, s)
+            .into_machine()
+            .into_machine()
+            .into_iter()
+            .filter(|m| !matches!(m, Move::ModalState(_)))
+            .collect()
+    }
+
+    #[test]
+    fn linearizer() {
+        let feed_rate = Decimal::new(100, 0);
+        assert_eq!(
+            linearize("G0 X10\nG3 X10 I-10 F100\n")[1..],
+            [
+                Move::Linear(point(0, 10, 0), feed_rate),
+                Move::Linear(point(-10, 0, 0), feed_rate),
+                Move::Linear(point(0, -10, 0), feed_rate),
+                Move::Linear(point(10, 0, 0), feed_rate),
+            ]
+        );
+    }
+
+    #[test]
+    fn linearizer_inverse_time() {
+        let feed_rate = Decimal::new(4, 0);
+        assert_eq!(
+            linearize("G0 X10\nG93 G2 X-10 I-10 F2\n")[1..],
+            [
+                Move::Linear(point(0, -10, 0), feed_rate),
+                Move::Linear(point(-10, 0, 0), feed_rate),
+            ]
+        );
+    }
+
+    #[test]
+    fn linearizer_error() {
+        let machine = Linearizer::new(Vec::new(), Decimal::new(4, 0));
+        assert_eq!(
+            error(machine, "G3 X10 I1\n"),
+            InterpreterError::MachineError(LinearizerError::ArcError(ArcError::EndpointMismatch))
+        );
+    }
+
+    #[test]
+    fn radius_center_sides() {
+        let center = |radius, clockwise| {
+            radius_center(
+                point(10, 0, 3),
+                point(0, -10, 5),
+                Decimal::new(radius, 0),
+                Plane::XY,
+                clockwise,
+            )
+        };
+        assert_eq!(center(10, true), Ok(point(0, 0, 3)));
+        assert_eq!(center(-10, true), Ok(point(10, -10, 3)));
+        assert_eq!(center(10, false), Ok(point(10, -10, 3)));
+        assert_eq!(center(-10, false), Ok(point(0, 0, 3)));
+    }
+
+    #[test]
+    fn radius_center_half_circle() {
+        let center = |x, radius| {
+            radius_center(
+                point(0, 0, 0),
+                Point::new(Decimal::new(x, 3), Decimal::new(0, 0), Decimal::new(0, 0)),
+                Decimal::new(radius, 0),
+                Plane::XY,
+                true,
+            )
+        };
+        assert_eq!(
+            center(20_000, 10),
+            Ok(Point::new(
+                Decimal::new(10, 0),
+                Decimal::new(0, 0),
+                Decimal::new(0, 0)
+            ))
+        );
+        assert_eq!(
+            center(20_001, 10),
+            Ok(Point::new(
+                Decimal::new(10_001, 3),
+                Decimal::new(0, 0),
+                Decimal::new(0, 0)
+            ))
+        );
+        assert_eq!(center(20_002, 10), Err(ArcError::InvalidRadius));
+    }
+
+    #[test]
+    fn radius_center_errors() {
+        let center = |end, radius| {
+            radius_center(
+                point(0, 0, 0),
+                end,
+                Decimal::new(radius, 0),
+                Plane::XY,
+                true,
+            )
+        };
+        assert_eq!(center(point(0, 0, 1), 1), Err(ArcError::InvalidRadius));
+        assert_eq!(center(point(1, 0, 0), 0), Err(ArcError::InvalidRadius));
+    }
+}
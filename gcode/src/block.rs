@@ -1,8 +1,15 @@
 use core::fmt::Debug;
 
+use crate::config::Config;
 use crate::decimal;
 use crate::decimal::{Decimal, DecimalParser};
+use crate::expression::{ExpressionParser, ExpressionToken};
+use crate::letters::Letters;
+use crate::o_word::OWordKeyword;
+#[cfg(feature = "position")]
+use crate::position::Position;
 use crate::significand::Significand;
+use crate::words::WordSet;
 
 #[cfg(feature = "defmt")]
 use defmt::Format;
@@ -15,6 +22,19 @@ pub trait BlockBuilder {
     /// The type used as the significand for [`Decimal`] numbers.
     type Significand: Significand;
 
+    /// The type used for indexes, parameter numbers, and O-word numbers. None of these can be
+    /// negative, so this is usually an unsigned type (e.g., `u8`.) It defaults to
+    /// [`BlockBuilder::Significand`].
+    ///
+    /// Indexes are parsed as [`BlockBuilder::Significand`] values until the `=` character, so an
+    /// index must fit in both types.
+    type Index: Significand = Self::Significand;
+
+    /// The type used as the significand for sequence numbers. Sequence numbers cannot be
+    /// negative, so this is usually an unsigned type (e.g., `u32`.) It defaults to
+    /// [`BlockBuilder::Index`].
+    type SequenceNumber: Significand = Self::Index;
+
     /// Called for the program start character.
     fn program_start(&mut self) -> Result<(), Self::Error>;
 
@@ -22,7 +42,7 @@ pub trait BlockBuilder {
     fn sequence_number(
         &mut self,
         alignment: bool,
-        number: Decimal<Self::Significand>,
+        number: Decimal<Self::SequenceNumber>,
     ) -> Result<(), Self::Error>;
 
     /// Called for a G code.
@@ -35,49 +55,255 @@ pub trait BlockBuilder {
     fn data(
         &mut self,
         address: char,
-        index: Option<Self::Significand>,
+        index: Option<Self::Index>,
         number: Decimal<Self::Significand>,
     ) -> Result<(), Self::Error>;
 
+    /// Called for a word whose value is read from a numbered parameter (e.g., `X#5`.)
+    ///
+    /// The address is passed as-is. This includes the `G` and `M` addresses.
+    ///
+    /// The default implementation does nothing.
+    fn parameter_word(
+        &mut self,
+        address: char,
+        index: Option<Self::Index>,
+        parameter: Self::Index,
+    ) -> Result<(), Self::Error> {
+        let _ = (address, index, parameter);
+        Ok(())
+    }
+
+    /// Called for an assignment to a numbered parameter (e.g., `#5=12.5`.)
+    ///
+    /// The default implementation does nothing.
+    fn parameter_assignment(
+        &mut self,
+        parameter: Self::Index,
+        number: Decimal<Self::Significand>,
+    ) -> Result<(), Self::Error> {
+        let _ = (parameter, number);
+        Ok(())
+    }
+
+    /// Called for each token of an expression.
+    ///
+    /// See [`ExpressionToken`] for the order of the tokens and how to evaluate them.
+    ///
+    /// The default implementation does nothing.
+    fn expression(
+        &mut self,
+        token: ExpressionToken<Self::Significand, Self::Index>,
+    ) -> Result<(), Self::Error> {
+        let _ = token;
+        Ok(())
+    }
+
+    /// Called for a word whose value is the result of the preceding expression (e.g.,
+    /// `X[1 + 2]`.)
+    ///
+    /// The address is passed as-is. This includes the `G` and `M` addresses.
+    ///
+    /// The default implementation does nothing.
+    fn expression_word(
+        &mut self,
+        address: char,
+        index: Option<Self::Index>,
+    ) -> Result<(), Self::Error> {
+        let _ = (address, index);
+        Ok(())
+    }
+
+    /// Called for an assignment of the result of the preceding expression to a numbered parameter
+    /// (e.g., `#5=[1 + 2]`.)
+    ///
+    /// The default implementation does nothing.
+    fn expression_assignment(&mut self, parameter: Self::Index) -> Result<(), Self::Error> {
+        let _ = parameter;
+        Ok(())
+    }
+
+    /// Called for an O-word and its keyword (e.g., `O100 call`.)
+    ///
+    /// The arguments of the O-word, if any, follow. Each argument is an expression, reported by
+    /// [`BlockBuilder::expression`] and followed by a call to [`BlockBuilder::o_word_argument`].
+    ///
+    /// The default implementation does nothing.
+    fn o_word(&mut self, number: Self::Index, keyword: OWordKeyword) -> Result<(), Self::Error> {
+        let _ = (number, keyword);
+        Ok(())
+    }
+
+    /// Called for an argument of the preceding O-word. The value of the argument is the result of
+    /// the preceding expression.
+    ///
+    /// The default implementation does nothing.
+    fn o_word_argument(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called for the checksum at the end of a block (e.g., `N123 G1 X1*57`.)
+    ///
+    /// `valid` is `true` if the checksum matches the XOR of the bytes of the block that precede the
+    /// `*` character.
+    ///
+    /// The default implementation does nothing.
+    fn checksum(&mut self, checksum: u8, valid: bool) -> Result<(), Self::Error> {
+        let _ = (checksum, valid);
+        Ok(())
+    }
+
     /// Called at the end of a block.
     fn end_block(&mut self) -> Result<(), Self::Error>;
+
+    /// Called instead of [`BlockBuilder::end_block`] at the end of a block that produced an
+    /// error, if recovery is enabled with [`Config::with_recovery`]. The builder should discard
+    /// the state of the partial block.
+    ///
+    /// The default implementation does nothing.
+    fn abort_block(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called at the start of a comment.
+    ///
+    /// A comment that starts with the `(` character can split a word (e.g., `G1 (c) 0` is `G10`),
+    /// and a word is only reported when it ends. The comment is therefore reported before the word
+    /// that precedes it if that word has not ended yet (e.g., in `X1 (c) Y2`, the comment is
+    /// reported before `X1`.) A comment that starts with the `;` character ends the preceding word
+    /// first.
+    ///
+    /// The default implementation does nothing.
+    fn comment_start(&mut self, kind: CommentKind) -> Result<(), Self::Error> {
+        let _ = kind;
+        Ok(())
+    }
+
+    /// Called for each character of the content of a comment.
+    ///
+    /// The characters are passed as-is. This includes whitespace and, for comments that span
+    /// lines, line feed characters.
+    ///
+    /// The default implementation does nothing.
+    fn comment_char(&mut self, c: char) -> Result<(), Self::Error> {
+        let _ = c;
+        Ok(())
+    }
+
+    /// Called at the end of a comment.
+    ///
+    /// The default implementation does nothing.
+    fn comment_end(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called at the end of each line of input, after [`BlockBuilder::end_block`] if the line
+    /// contained a block. This includes a last line without a line feed character (`\n`) at the
+    /// end of the input. It is not called for the lines of an aborted block or for line feed
+    /// characters in comments.
+    ///
+    /// Unlike [`BlockBuilder::end_block`], it is also called for lines without a block (e.g., a
+    /// line with only a comment.)
+    ///
+    /// The default implementation does nothing.
+    fn end_line(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called with the position of the first character of each word, before the word itself.
+    ///
+    /// The default implementation does nothing.
+    #[cfg(feature = "position")]
+    fn word_start(&mut self, position: Position) -> Result<(), Self::Error> {
+        let _ = position;
+        Ok(())
+    }
+}
+
+/// The kinds of comments.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum CommentKind {
+    /// A comment that starts with the "control out" character `(` and ends with the "control in"
+    /// character `)`.
+    ControlOut,
+
+    /// A comment that starts with the `;` character and ends at the next line feed character
+    /// (`\n`.)
+    Semicolon,
 }
 
 /// The G-code parser.
+///
+/// `S`, `I`, and `N` are the [`BlockBuilder::Significand`], [`BlockBuilder::Index`], and
+/// [`BlockBuilder::SequenceNumber`] types of the builder.
+///
+/// Expressions are parsed with a stack of pending brackets, functions, and operators. `DEPTH` is
+/// the maximum depth of this stack.
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(Format))]
-pub struct BlockParser<S>
+pub struct BlockParser<S, I = S, N = I, const DEPTH: usize = 8>
 where
     S: Significand,
+    I: Significand,
+    N: Significand,
 {
-    control_out: bool,
-    state: State<S>,
+    config: Config,
+    comment: Option<CommentKind>,
+    checksum: u8,
+    line: bool,
+    #[cfg(feature = "position")]
+    position: Position,
+    state: State<I>,
     decimal_parser: DecimalParser<S>,
+    unsigned_parser: DecimalParser<I>,
+    sequence_number_parser: DecimalParser<N>,
+    expression_parser: ExpressionParser<S, I, DEPTH>,
+    words: WordSet,
 }
 
-impl<S> Default for BlockParser<S>
+impl<S, I, N, const DEPTH: usize> Default for BlockParser<S, I, N, DEPTH>
 where
     S: Significand,
+    I: Significand + TryFrom<S>,
+    N: Significand,
 {
     fn default() -> Self {
-        Self {
-            control_out: false,
-            state: State::Start,
-            decimal_parser: DecimalParser::default(),
-        }
+        Self::new(Config::default())
     }
 }
 
-impl<S> BlockParser<S>
+impl<S, I, N, const DEPTH: usize> BlockParser<S, I, N, DEPTH>
 where
     S: Significand,
+    I: Significand + TryFrom<S>,
+    N: Significand,
 {
+    /// Creates a new [`BlockParser`] that accepts the dialect selected by the [`Config`].
+    #[must_use]
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            comment: None,
+            checksum: 0,
+            line: false,
+            #[cfg(feature = "position")]
+            position: Position::new(),
+            state: State::Start,
+            decimal_parser: DecimalParser::default(),
+            unsigned_parser: DecimalParser::default(),
+            sequence_number_parser: DecimalParser::default(),
+            expression_parser: ExpressionParser::default(),
+            words: WordSet::default(),
+        }
+    }
+
     /// Try to feed a string to the `BlockParser`.
     #[inline(never)]
     pub fn try_feed_str<T, B>(&mut self, s: T, builder: &mut B) -> Result<(), Error<B::Error>>
     where
         T: AsRef<str>,
-        B: BlockBuilder<Significand = S>,
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
     {
         let s = s.as_ref();
         for c in s.chars() {
@@ -89,175 +315,748 @@ where
     /// Try to feed a single character to the `BlockParser`.
     pub fn try_feed_char<B>(&mut self, c: char, builder: &mut B) -> Result<(), Error<B::Error>>
     where
-        B: BlockBuilder<Significand = S>,
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        self.try_feed(c, c.len_utf8(), builder)
+    }
+
+    /// Try to feed a slice of bytes to the `BlockParser`.
+    ///
+    /// See [`BlockParser::try_feed_byte`].
+    #[inline(never)]
+    pub fn try_feed_bytes<T, B>(&mut self, bytes: T, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        T: AsRef<[u8]>,
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        let bytes = bytes.as_ref();
+        for &byte in bytes {
+            self.try_feed_byte(byte, builder)?;
+        }
+        Ok(())
+    }
+
+    /// Try to feed a single byte to the `BlockParser`.
+    ///
+    /// The input is treated as ASCII, without UTF-8 validation. A non-ASCII byte is only valid in
+    /// a comment, where it is passed to [`BlockBuilder::comment_char`] as the character with the
+    /// same code point (i.e., as ISO-8859-1), unless [`Config::with_ascii_comments`] is enabled.
+    pub fn try_feed_byte<B>(&mut self, byte: u8, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        self.try_feed(char::from(byte), 1, builder)
+    }
+
+    /// Feeds a character that was encoded as `len` bytes of input.
+    fn try_feed<B>(&mut self, c: char, len: usize, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "position")] {
+                let result = self
+                    .feed_char(c, len, builder)
+                    .map_err(|error| error.with_position(self.position));
+                self.position.advance(c, len);
+                result
+            } else {
+                self.feed_char(c, len, builder)
+            }
+        }
+    }
+
+    /// Try to end the [`BlockParser`] (for example, at the end of a G-code file.)
+    ///
+    /// A comment that is still open (e.g., a `(` comment without a `)` character) is ended.
+    pub fn try_end<B>(&mut self, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "position")] {
+                self.end(builder)
+                    .map_err(|error| error.with_position(self.position))
+            } else {
+                self.end(builder)
+            }
+        }
+    }
+
+    /// Returns the position of the next character.
+    #[cfg(feature = "position")]
+    #[must_use]
+    pub const fn position(&self) -> Position {
+        self.position
+    }
+
+    fn feed_char<B>(&mut self, c: char, len: usize, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
     {
-        if '(' == c {
-            self.control_out = true;
-        } else if self.control_out {
-            self.control_out = ')' != c;
+        self.line = true;
+        if matches!(self.state, State::Recovery) {
+            return if '\n' == c { self.end(builder) } else { Ok(()) };
+        }
+        let ends_block = '\n' == c && Some(CommentKind::ControlOut) != self.comment;
+        let result = self.parse_char(c, len, builder);
+        if result.is_err() && self.config.recovery() {
+            self.comment = None;
+            if ends_block {
+                self.abort_block(builder)?;
+            } else {
+                self.state = State::Recovery;
+            }
+        }
+        result
+    }
+
+    fn end<B>(&mut self, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        if matches!(self.state, State::Recovery) {
+            return self.abort_block(builder);
+        }
+        let result = self.finish_block(builder);
+        if result.is_err() && self.config.recovery() {
+            self.comment = None;
+            self.abort_block(builder)?;
+        }
+        result
+    }
+
+    fn abort_block<B>(&mut self, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        self.state = State::Start;
+        self.checksum = 0;
+        self.line = false;
+        self.words.clear();
+        builder
+            .abort_block()
+            .map_err(Error::new_block_builder_error)
+    }
+
+    fn parse_char<B>(&mut self, c: char, len: usize, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        let checksum_start = self.is_checksum_start(c);
+        if self.config.checksums()
+            && !checksum_start
+            && '\n' != c
+            && !matches!(self.state, State::Checksum { .. })
+        {
+            self.update_checksum(c, len);
+        }
+        if let Some(kind) = self.comment {
+            match (kind, c) {
+                (CommentKind::ControlOut, ')') => {
+                    self.comment = None;
+                    builder
+                        .comment_end()
+                        .map_err(Error::new_block_builder_error)?;
+                }
+                (CommentKind::Semicolon, '\n') => {
+                    self.finish_block(builder)?;
+                }
+                (_, c) => {
+                    if !c.is_ascii() && self.config.ascii_comments() {
+                        return Err(Error::new_invalid_character());
+                    }
+                    builder
+                        .comment_char(c)
+                        .map_err(Error::new_block_builder_error)?;
+                }
+            }
+        } else if checksum_start {
+            self.end_state(builder)?;
+            self.state = State::Checksum { checksum: None };
+        } else if '(' == c {
+            self.start_comment(CommentKind::ControlOut, builder)?;
+        } else if ';' == c && self.config.semicolon_comments() {
+            self.end_word_before_comment(builder)?;
+            self.start_comment(CommentKind::Semicolon, builder)?;
         } else if '\n' == c {
-            self.try_end(builder)?;
+            self.finish_block(builder)?;
         } else if !Self::is_whitespace(c) {
-            match self.state {
-                State::Start => match c {
-                    '%' => {
-                        builder
-                            .program_start()
-                            .map_err(Error::new_block_builder_error)?;
-                    }
-                    '/' => {
-                        self.state = State::BlockSkip;
-                    }
-                    ':' | 'N' | 'n' => {
-                        let alignment = ':' == c;
-                        self.state = State::Decimal(DecimalState::SequenceNumber { alignment });
-                        self.decimal_parser = DecimalParser::default();
-                    }
-                    c => {
-                        self.start_word::<B>(c)?;
-                    }
-                },
-                State::BlockSkip => {
-                    // do nothing
+            self.parse_state(c, builder)?;
+        }
+        Ok(())
+    }
+
+    fn update_checksum(&mut self, c: char, len: usize) {
+        if 1 == len {
+            // A single byte of input is either ASCII or ISO-8859-1, so it cannot truncate.
+            #[allow(clippy::cast_possible_truncation)]
+            let byte = c as u8;
+            self.checksum ^= byte;
+        } else {
+            let mut buffer = [0; 4];
+            for byte in c.encode_utf8(&mut buffer).bytes() {
+                self.checksum ^= byte;
+            }
+        }
+    }
+
+    fn parse_state<B>(&mut self, c: char, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        match self.state {
+            State::Start => match c {
+                '%' => {
+                    builder
+                        .program_start()
+                        .map_err(Error::new_block_builder_error)?;
                 }
-                State::Index { address } => match c {
-                    '0'..='9' => {
-                        self.decimal_parser.try_feed(c)?;
-                    }
-                    '=' => {
-                        let index = self.decimal_parser.try_end()?;
-                        assert_eq!(index.negative_exponent(), 0);
-                        let index = index.significand();
-                        self.state = State::Decimal(DecimalState::IndexedWord { address, index });
-                        self.decimal_parser = DecimalParser::default();
-                    }
-                    c => {
-                        let decimal_state = DecimalState::Word { address };
-                        self.state = State::Decimal(decimal_state);
-                        self.parse_decimal(decimal_state, c, builder)?;
-                    }
-                },
-                State::Decimal(decimal_state) => {
+                '/' => {
+                    self.state = State::BlockSkip;
+                }
+                ':' | 'N' | 'n' => {
+                    #[cfg(feature = "position")]
+                    builder
+                        .word_start(self.position)
+                        .map_err(Error::new_block_builder_error)?;
+                    let alignment = ':' == c;
+                    self.state = State::SequenceNumber { alignment };
+                    self.sequence_number_parser = DecimalParser::default();
+                }
+                c => {
+                    self.start_word(c, builder)?;
+                }
+            },
+            State::BlockSkip | State::Recovery => {
+                // do nothing
+            }
+            State::Index { address } => match c {
+                '0'..='9' => {
+                    self.decimal_parser.try_feed(c)?;
+                }
+                '=' => {
+                    let index = self.end_index::<B>()?;
+                    self.state = State::Decimal(DecimalState::IndexedWord { address, index });
+                    self.decimal_parser = DecimalParser::default();
+                }
+                c => {
+                    let decimal_state = DecimalState::Word { address };
+                    self.state = State::Decimal(decimal_state);
                     self.parse_decimal(decimal_state, c, builder)?;
                 }
-            };
-        };
+            },
+            State::Parameter(parameter_state) => match (parameter_state, c) {
+                (_, '0'..='9') => {
+                    self.unsigned_parser.try_feed(c)?;
+                }
+                (ParameterState::Assignment, '=') => {
+                    let parameter = self.end_unsigned::<B>()?;
+                    self.state = State::Decimal(DecimalState::ParameterAssignment { parameter });
+                    self.decimal_parser = DecimalParser::default();
+                }
+                (ParameterState::Assignment, _) => {
+                    return Err(Error::new_invalid_character());
+                }
+                (ParameterState::Word { address, index }, c) => {
+                    self.end_parameter_word(address, index, builder)?;
+                    self.start_word(c, builder)?;
+                }
+            },
+            State::SequenceNumber { alignment } => {
+                self.parse_sequence_number(alignment, c, builder)?;
+            }
+            State::Decimal(decimal_state) => {
+                self.parse_decimal(decimal_state, c, builder)?;
+            }
+            State::Expression(target) => {
+                if self.expression_parser.try_feed(c, builder)? {
+                    self.end_expression(target, builder)?;
+                }
+            }
+            State::NextWord => {
+                self.start_word(c, builder)?;
+            }
+            State::OWord | State::OWordKeyword { .. } | State::OWordArguments => {
+                self.parse_o_word(c, builder)?;
+            }
+            State::Checksum { checksum } => {
+                self.parse_checksum(checksum, c)?;
+            }
+        }
         Ok(())
     }
 
-    /// Try to end the [`BlockParser`] (for example, at the end of a G-code file.)
-    pub fn try_end<B>(&mut self, builder: &mut B) -> Result<(), Error<B::Error>>
+    fn finish_block<B>(&mut self, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        // A `;` comment ends at the line feed. A `(` comment only gets here at the end of the
+        // input, where it is closed the same way.
+        if self.comment.take().is_some() {
+            builder
+                .comment_end()
+                .map_err(Error::new_block_builder_error)?;
+        }
+        let started = !matches!(self.state, State::Start | State::BlockSkip);
+        if let State::Checksum { checksum } = self.state {
+            let checksum = checksum.ok_or_else(Error::new_incomplete)?;
+            builder
+                .checksum(checksum, checksum == self.checksum)
+                .map_err(Error::new_block_builder_error)?;
+        } else {
+            self.end_state(builder)?;
+        }
+        self.state = State::Start;
+        self.checksum = 0;
+        self.words.clear();
+        if started {
+            builder
+                .end_block()
+                .map_err(Error::new_block_builder_error)?;
+        }
+        if self.line {
+            self.line = false;
+            builder.end_line().map_err(Error::new_block_builder_error)?;
+        }
+        Ok(())
+    }
+
+    /// Ends the word, if any, that is in progress.
+    fn end_state<B>(&mut self, builder: &mut B) -> Result<(), Error<B::Error>>
     where
-        B: BlockBuilder<Significand = S>,
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
     {
         match self.state {
-            State::Start | State::BlockSkip => {
-                self.state = State::Start;
-                return Ok(()); // Return early to avoid endling a block that never started.
+            State::Start
+            | State::BlockSkip
+            | State::NextWord
+            | State::OWordArguments
+            | State::Recovery => {
+                // do nothing
+            }
+            State::SequenceNumber { alignment } => {
+                self.end_sequence_number(alignment, builder)?;
             }
             State::Index { address } => {
                 let decimal_state = DecimalState::Word { address };
                 self.state = State::Decimal(decimal_state);
                 self.end_decimal(decimal_state, builder)?;
             }
+            State::Parameter(ParameterState::Assignment)
+            | State::Expression(_)
+            | State::OWord
+            | State::Checksum { .. } => {
+                return Err(Error::new_incomplete());
+            }
+            State::Parameter(ParameterState::Word { address, index }) => {
+                self.end_parameter_word(address, index, builder)?;
+            }
             State::Decimal(decimal_state) => {
                 self.end_decimal(decimal_state, builder)?;
             }
+            State::OWordKeyword { number, keyword } => {
+                Self::end_o_word_keyword(number, &keyword, builder)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_checksum<E>(&mut self, checksum: Option<u8>, c: char) -> Result<(), Error<E>> {
+        let digit = c.to_digit(10).ok_or_else(Error::new_invalid_character)?;
+        let checksum = checksum
+            .unwrap_or(0)
+            .checked_mul(10)
+            .and_then(|checksum| checksum.checked_add(u8::try_from(digit).ok()?))
+            .ok_or_else(Error::new_capacity)?;
+        self.state = State::Checksum {
+            checksum: Some(checksum),
         };
-        self.state = State::Start;
-        builder
-            .end_block()
-            .map_err(Error::new_block_builder_error)?;
         Ok(())
     }
 
+    const fn is_checksum_start(&self, c: char) -> bool {
+        '*' == c
+            && self.config.checksums()
+            && self.comment.is_none()
+            && !matches!(
+                self.state,
+                State::Start | State::BlockSkip | State::Expression(_) | State::Checksum { .. }
+            )
+    }
+
+    /// Ends the pending word, if any, before a `;` comment. The comment runs to the end of the
+    /// line, so the word cannot continue after it.
+    fn end_word_before_comment<B>(&mut self, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        match self.state {
+            State::SequenceNumber { .. }
+            | State::Index { .. }
+            | State::Parameter(ParameterState::Word { .. })
+            | State::Decimal(_) => {
+                self.end_state(builder)?;
+                self.state = State::NextWord;
+            }
+            State::OWordKeyword { .. } => {
+                self.end_state(builder)?;
+                self.state = State::OWordArguments;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn start_comment<B>(
+        &mut self,
+        kind: CommentKind,
+        builder: &mut B,
+    ) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        self.comment = Some(kind);
+        builder
+            .comment_start(kind)
+            .map_err(Error::new_block_builder_error)
+    }
+
     const fn is_whitespace(c: char) -> bool {
         matches!(c, ' ' | '\t' | '\r')
     }
 
-    fn start_word<B>(&mut self, address: char) -> Result<(), Error<B::Error>>
+    // The builder is only used to report the position of the word.
+    #[cfg_attr(not(feature = "position"), allow(clippy::needless_pass_by_ref_mut))]
+    fn start_word<B>(&mut self, address: char, builder: &mut B) -> Result<(), Error<B::Error>>
     where
-        B: BlockBuilder<Significand = S>,
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
     {
+        #[cfg(feature = "position")]
+        builder
+            .word_start(self.position)
+            .map_err(Error::new_block_builder_error)?;
+        #[cfg(not(feature = "position"))]
+        let _ = builder;
         match address {
             'G' | 'M' | 'g' | 'm' => {
                 self.state = State::Decimal(DecimalState::Word { address });
                 self.decimal_parser = DecimalParser::default();
                 Ok(())
             }
+            'O' | 'o' if self.config.o_words() => {
+                self.state = State::OWord;
+                self.unsigned_parser = DecimalParser::default();
+                Ok(())
+            }
             'A'..='Z' | 'a'..='z' => {
                 self.state = State::Index { address };
                 self.decimal_parser = DecimalParser::default();
                 Ok(())
             }
+            '#' if self.config.parameters() => {
+                self.state = State::Parameter(ParameterState::Assignment);
+                self.unsigned_parser = DecimalParser::default();
+                Ok(())
+            }
             _ => Err(Error::new_invalid_character()),
         }
     }
 
-    fn parse_decimal<B>(
+    fn start_value<B>(
         &mut self,
-        decimal_state: DecimalState<S>,
+        decimal_state: DecimalState<I>,
         c: char,
         builder: &mut B,
     ) -> Result<(), Error<B::Error>>
     where
-        B: BlockBuilder<Significand = S>,
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
     {
-        if let Err(e) = self.decimal_parser.try_feed(c) {
-            match e {
-                decimal::Error::InvalidCharacter => {
-                    self.end_decimal(decimal_state, builder)?;
-                    self.start_word::<B>(c)?;
-                }
-                _ => {
-                    return Err(e.into());
-                }
+        let target = match decimal_state {
+            DecimalState::IndexedWord { address, index } => ExpressionTarget::Word {
+                address,
+                index: Some(index),
+            },
+            DecimalState::Word { address } => ExpressionTarget::Word {
+                address,
+                index: None,
+            },
+            DecimalState::ParameterAssignment { parameter } => {
+                ExpressionTarget::Assignment { parameter }
             }
         };
-        Ok(())
+        match (target, c) {
+            (ExpressionTarget::Word { address, index }, '#') if self.config.parameters() => {
+                self.state = State::Parameter(ParameterState::Word { address, index });
+                self.unsigned_parser = DecimalParser::default();
+                Ok(())
+            }
+            (target, '[') if self.config.expressions() => {
+                self.state = State::Expression(target);
+                self.expression_parser = ExpressionParser::default();
+                self.expression_parser.try_feed(c, builder)?;
+                Ok(())
+            }
+            _ if self.is_value_start(c) => Err(Error::new_invalid_character()),
+            _ => Err(Error::new_incomplete()),
+        }
     }
 
-    fn end_decimal<B>(
+    const fn is_value_start(&self, c: char) -> bool {
+        ('#' == c && self.config.parameters()) || ('[' == c && self.config.expressions())
+    }
+
+    fn end_expression<B>(
         &mut self,
-        decimal_state: DecimalState<S>,
+        target: ExpressionTarget<I>,
         builder: &mut B,
     ) -> Result<(), Error<B::Error>>
     where
-        B: BlockBuilder<Significand = S>,
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
     {
-        let number = self.decimal_parser.try_end()?;
-        let result = match decimal_state {
-            DecimalState::SequenceNumber { alignment } => {
-                builder.sequence_number(alignment, number)
+        let result = match target {
+            ExpressionTarget::Word { address, index } => {
+                self.state = State::NextWord;
+                self.insert_word(address, index)?;
+                builder.expression_word(address, index)
             }
-            DecimalState::IndexedWord { address, index } => {
-                builder.data(address, Some(index), number)
+            ExpressionTarget::Assignment { parameter } => {
+                self.state = State::NextWord;
+                builder.expression_assignment(parameter)
+            }
+            ExpressionTarget::OWordArgument => {
+                self.state = State::OWordArguments;
+                builder.o_word_argument()
             }
-            DecimalState::Word { address } => Self::end_word(address, number, builder),
         };
         result.map_err(Error::new_block_builder_error)
     }
 
-    fn end_word<B>(address: char, number: Decimal<S>, builder: &mut B) -> Result<(), B::Error>
+    fn parse_o_word<B>(&mut self, c: char, builder: &mut B) -> Result<(), Error<B::Error>>
     where
-        B: BlockBuilder<Significand = S>,
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
     {
-        match address {
-            'G' | 'g' => builder.g_code(number),
-            'M' | 'm' => builder.m_code(number),
-            address => builder.data(address, None, number),
+        match self.state {
+            State::OWord => {
+                if c.is_ascii_digit() {
+                    self.unsigned_parser.try_feed(c)?;
+                } else {
+                    let number = self.end_unsigned::<B>()?;
+                    let mut keyword = Letters::default();
+                    if !keyword.try_push(c) {
+                        return Err(Error::new_invalid_character());
+                    }
+                    self.state = State::OWordKeyword { number, keyword };
+                }
+            }
+            State::OWordKeyword {
+                number,
+                mut keyword,
+            } => {
+                if keyword.try_push(c) {
+                    self.state = State::OWordKeyword { number, keyword };
+                } else if c.is_ascii_alphabetic() {
+                    return Err(Error::new_invalid_character());
+                } else {
+                    Self::end_o_word_keyword(number, &keyword, builder)?;
+                    self.start_o_word_argument(c, builder)?;
+                }
+            }
+            _ => {
+                self.start_o_word_argument(c, builder)?;
+            }
         }
+        Ok(())
     }
-}
 
-/// The categories of [`BlockParser`] errors.
-#[derive(Debug, PartialEq)]
-#[cfg_attr(feature = "defmt", derive(Format))]
-pub enum ErrorKind<E> {
-    /// The [`BlockBuilder`] produced an error.
-    BlockBuilderError(E),
+    fn end_o_word_keyword<B>(
+        number: I,
+        keyword: &Letters<9>,
+        builder: &mut B,
+    ) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        let keyword =
+            OWordKeyword::from_name(keyword.as_bytes()).ok_or_else(Error::new_invalid_character)?;
+        builder
+            .o_word(number, keyword)
+            .map_err(Error::new_block_builder_error)
+    }
+
+    fn start_o_word_argument<B>(&mut self, c: char, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        if '[' == c {
+            self.state = State::Expression(ExpressionTarget::OWordArgument);
+            self.expression_parser = ExpressionParser::default();
+            self.expression_parser.try_feed(c, builder)?;
+            Ok(())
+        } else {
+            Err(Error::new_invalid_character())
+        }
+    }
+
+    fn end_parameter_word<B>(
+        &mut self,
+        address: char,
+        index: Option<I>,
+        builder: &mut B,
+    ) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        let parameter = self.end_unsigned::<B>()?;
+        self.insert_word(address, index)?;
+        builder
+            .parameter_word(address, index, parameter)
+            .map_err(Error::new_block_builder_error)
+    }
+
+    fn end_unsigned<B>(&self) -> Result<I, Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        let number = self.unsigned_parser.try_end()?;
+        assert_eq!(number.negative_exponent(), 0);
+        Ok(number.significand())
+    }
+
+    fn end_index<B>(&self) -> Result<I, Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        let number = self.decimal_parser.try_end()?;
+        assert_eq!(number.negative_exponent(), 0);
+        I::try_from(number.significand()).map_err(|_| Error::new_capacity())
+    }
+
+    fn parse_sequence_number<B>(
+        &mut self,
+        alignment: bool,
+        c: char,
+        builder: &mut B,
+    ) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        match self.sequence_number_parser.try_feed(c) {
+            Err(decimal::Error::InvalidCharacter) if self.sequence_number_parser.is_empty() => {
+                if self.is_value_start(c) {
+                    Err(Error::new_invalid_character())
+                } else {
+                    Err(Error::new_incomplete())
+                }
+            }
+            Err(decimal::Error::InvalidCharacter) => {
+                self.end_sequence_number(alignment, builder)?;
+                self.start_word(c, builder)
+            }
+            result => Ok(result?),
+        }
+    }
+
+    fn end_sequence_number<B>(
+        &self,
+        alignment: bool,
+        builder: &mut B,
+    ) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        let number = self.sequence_number_parser.try_end()?;
+        builder
+            .sequence_number(alignment, number)
+            .map_err(Error::new_block_builder_error)
+    }
+
+    fn parse_decimal<B>(
+        &mut self,
+        decimal_state: DecimalState<I>,
+        c: char,
+        builder: &mut B,
+    ) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        if let Err(e) = self.decimal_parser.try_feed(c) {
+            match e {
+                decimal::Error::InvalidCharacter if self.decimal_parser.is_empty() => {
+                    self.start_value(decimal_state, c, builder)?;
+                }
+                decimal::Error::InvalidCharacter => {
+                    self.end_decimal(decimal_state, builder)?;
+                    self.start_word(c, builder)?;
+                }
+                _ => {
+                    return Err(e.into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn end_decimal<B>(
+        &mut self,
+        decimal_state: DecimalState<I>,
+        builder: &mut B,
+    ) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        let number = self.decimal_parser.try_end()?;
+        let result = match decimal_state {
+            DecimalState::IndexedWord { address, index } => {
+                self.insert_word(address, Some(index))?;
+                builder.data(address, Some(index), number)
+            }
+            DecimalState::Word { address } => {
+                self.insert_word(address, None)?;
+                Self::end_word(address, number, builder)
+            }
+            DecimalState::ParameterAssignment { parameter } => {
+                builder.parameter_assignment(parameter, number)
+            }
+        };
+        result.map_err(Error::new_block_builder_error)
+    }
+
+    /// Records a word of the block, if duplicate words are rejected. The G and M addresses are not
+    /// recorded.
+    fn insert_word<E>(&mut self, address: char, index: Option<I>) -> Result<(), Error<E>> {
+        let checked = self.config.unique_words() && !matches!(address, 'G' | 'M' | 'g' | 'm');
+        if !checked {
+            return Ok(());
+        }
+        match self.words.try_insert(address, index) {
+            Some(true) => Ok(()),
+            Some(false) => Err(Error::new_duplicate_word()),
+            None => Err(Error::new_unsupported_index()),
+        }
+    }
+
+    fn end_word<B>(address: char, number: Decimal<S>, builder: &mut B) -> Result<(), B::Error>
+    where
+        B: BlockBuilder<Significand = S, Index = I, SequenceNumber = N>,
+    {
+        match address {
+            'G' | 'g' => builder.g_code(number),
+            'M' | 'm' => builder.m_code(number),
+            address => builder.data(address, None, number),
+        }
+    }
+}
 
-    /// A numeric value exceeded the bounds of the [`Significand`] numeric type.
+/// The categories of [`BlockParser`] errors.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum ErrorKind<E> {
+    /// The [`BlockBuilder`] produced an error.
+    BlockBuilderError(E),
+
+    /// A numeric value exceeded the bounds of the [`Significand`] numeric type, or a checksum
+    /// exceeded 255.
     Capacity,
 
     /// The [`BlockParser`] encountered an incomplete word, index, or number.
@@ -265,6 +1064,13 @@ pub enum ErrorKind<E> {
 
     /// The [`BlockParser`] encountered an invalid character.
     InvalidCharacter,
+
+    /// The [`BlockParser`] encountered a duplicate word (see [`Config::with_unique_words`].)
+    DuplicateWord,
+
+    /// The [`BlockParser`] encountered an index greater than 31, which cannot be checked for
+    /// duplicates (see [`Config::with_unique_words`].)
+    UnsupportedIndex,
 }
 
 impl<E> Eq for ErrorKind<E> where E: Eq {}
@@ -274,6 +1080,8 @@ impl<E> Eq for ErrorKind<E> where E: Eq {}
 #[cfg_attr(feature = "defmt", derive(Format))]
 pub struct Error<E> {
     kind: ErrorKind<E>,
+    #[cfg(feature = "position")]
+    position: Position,
 }
 
 impl<E> Eq for Error<E> where E: Eq {}
@@ -289,27 +1097,77 @@ impl<E> From<decimal::Error> for Error<E> {
 }
 
 impl<E> Error<E> {
-    const fn new_block_builder_error(error: E) -> Self {
+    pub(crate) const fn new_block_builder_error(error: E) -> Self {
         Self {
             kind: ErrorKind::BlockBuilderError(error),
+            #[cfg(feature = "position")]
+            position: Position::new(),
         }
     }
 
-    const fn new_capacity() -> Self {
+    pub(crate) const fn new_capacity() -> Self {
         Self {
             kind: ErrorKind::Capacity,
+            #[cfg(feature = "position")]
+            position: Position::new(),
         }
     }
 
-    const fn new_incomplete() -> Self {
+    pub(crate) const fn new_incomplete() -> Self {
         Self {
             kind: ErrorKind::Incomplete,
+            #[cfg(feature = "position")]
+            position: Position::new(),
         }
     }
 
-    const fn new_invalid_character() -> Self {
+    pub(crate) const fn new_invalid_character() -> Self {
         Self {
             kind: ErrorKind::InvalidCharacter,
+            #[cfg(feature = "position")]
+            position: Position::new(),
+        }
+    }
+
+    pub(crate) const fn new_duplicate_word() -> Self {
+        Self {
+            kind: ErrorKind::DuplicateWord,
+            #[cfg(feature = "position")]
+            position: Position::new(),
+        }
+    }
+
+    pub(crate) const fn new_unsupported_index() -> Self {
+        Self {
+            kind: ErrorKind::UnsupportedIndex,
+            #[cfg(feature = "position")]
+            position: Position::new(),
+        }
+    }
+
+    #[cfg(feature = "position")]
+    pub(crate) fn with_position(self, position: Position) -> Self {
+        Self { position, ..self }
+    }
+
+    /// Converts the error to another [`BlockBuilder`] error type. `map` converts the error of
+    /// [`ErrorKind::BlockBuilderError`], possibly to another kind.
+    pub(crate) fn map_block_builder_error<F, M>(self, map: M) -> Error<F>
+    where
+        M: FnOnce(E) -> ErrorKind<F>,
+    {
+        let kind = match self.kind {
+            ErrorKind::BlockBuilderError(error) => map(error),
+            ErrorKind::Capacity => ErrorKind::Capacity,
+            ErrorKind::Incomplete => ErrorKind::Incomplete,
+            ErrorKind::InvalidCharacter => ErrorKind::InvalidCharacter,
+            ErrorKind::DuplicateWord => ErrorKind::DuplicateWord,
+            ErrorKind::UnsupportedIndex => ErrorKind::UnsupportedIndex,
+        };
+        Error {
+            kind,
+            #[cfg(feature = "position")]
+            position: self.position,
         }
     }
 
@@ -317,6 +1175,13 @@ impl<E> Error<E> {
     pub const fn kind(&self) -> &ErrorKind<E> {
         &self.kind
     }
+
+    /// Returns the position of the character that caused the error, or the position of the end
+    /// of the input for errors of [`BlockParser::try_end`].
+    #[cfg(feature = "position")]
+    pub const fn position(&self) -> Position {
+        self.position
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -327,8 +1192,38 @@ where
 {
     Start,
     BlockSkip,
+    SequenceNumber { alignment: bool },
     Index { address: char },
+    Parameter(ParameterState<S>),
     Decimal(DecimalState<S>),
+    Expression(ExpressionTarget<S>),
+    NextWord,
+    OWord,
+    OWordKeyword { number: S, keyword: Letters<9> },
+    OWordArguments,
+    Checksum { checksum: Option<u8> },
+    Recovery,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+enum ParameterState<S>
+where
+    S: Significand,
+{
+    Assignment,
+    Word { address: char, index: Option<S> },
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+enum ExpressionTarget<S>
+where
+    S: Significand,
+{
+    Assignment { parameter: S },
+    Word { address: char, index: Option<S> },
+    OWordArgument,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -337,9 +1232,9 @@ enum DecimalState<S>
 where
     S: Significand,
 {
-    SequenceNumber { alignment: bool },
     IndexedWord { address: char, index: S },
     Word { address: char },
+    ParameterAssignment { parameter: S },
 }
 
 #[cfg(test)]
@@ -350,6 +1245,7 @@ mod tests {
     use std::vec::Vec;
 
     use super::*;
+    use crate::expression::{BinaryOperator, Function};
 
     #[derive(Debug, Eq, PartialEq)]
     enum Item {
@@ -369,7 +1265,43 @@ mod tests {
             index: Option<i32>,
             number: Decimal<i32>,
         },
+        ParameterWord {
+            address: char,
+            index: Option<i32>,
+            parameter: i32,
+        },
+        ParameterAssignment {
+            parameter: i32,
+            number: Decimal<i32>,
+        },
+        Expression {
+            token: ExpressionToken<i32>,
+        },
+        ExpressionWord {
+            address: char,
+            index: Option<i32>,
+        },
+        ExpressionAssignment {
+            parameter: i32,
+        },
+        OWord {
+            number: i32,
+            keyword: OWordKeyword,
+        },
+        OWordArgument,
+        Checksum {
+            checksum: u8,
+            valid: bool,
+        },
         End,
+        Abort,
+        CommentStart {
+            kind: CommentKind,
+        },
+        CommentChar {
+            c: char,
+        },
+        CommentEnd,
     }
 
     impl BlockBuilder for Vec<Item> {
@@ -414,10 +1346,98 @@ mod tests {
             Ok(())
         }
 
+        fn parameter_word(
+            &mut self,
+            address: char,
+            index: Option<i32>,
+            parameter: i32,
+        ) -> Result<(), Self::Error> {
+            self.push(Item::ParameterWord {
+                address,
+                index,
+                parameter,
+            });
+            Ok(())
+        }
+
+        fn parameter_assignment(
+            &mut self,
+            parameter: i32,
+            number: Decimal<i32>,
+        ) -> Result<(), Self::Error> {
+            self.push(Item::ParameterAssignment { parameter, number });
+            Ok(())
+        }
+
+        fn expression(&mut self, token: ExpressionToken<i32>) -> Result<(), Self::Error> {
+            self.push(Item::Expression { token });
+            Ok(())
+        }
+
+        fn expression_word(
+            &mut self,
+            address: char,
+            index: Option<i32>,
+        ) -> Result<(), Self::Error> {
+            self.push(Item::ExpressionWord { address, index });
+            Ok(())
+        }
+
+        fn expression_assignment(&mut self, parameter: i32) -> Result<(), Self::Error> {
+            self.push(Item::ExpressionAssignment { parameter });
+            Ok(())
+        }
+
+        fn o_word(&mut self, number: i32, keyword: OWordKeyword) -> Result<(), Self::Error> {
+            self.push(Item::OWord { number, keyword });
+            Ok(())
+        }
+
+        fn o_word_argument(&mut self) -> Result<(), Self::Error> {
+            self.push(Item::OWordArgument);
+            Ok(())
+        }
+
+        fn checksum(&mut self, checksum: u8, valid: bool) -> Result<(), Self::Error> {
+            self.push(Item::Checksum { checksum, valid });
+            Ok(())
+        }
+
         fn end_block(&mut self) -> Result<(), Self::Error> {
             self.push(Item::End);
             Ok(())
         }
+
+        fn abort_block(&mut self) -> Result<(), Self::Error> {
+            self.push(Item::Abort);
+            Ok(())
+        }
+
+        fn comment_start(&mut self, kind: CommentKind) -> Result<(), Self::Error> {
+            self.push(Item::CommentStart { kind });
+            Ok(())
+        }
+
+        fn comment_char(&mut self, c: char) -> Result<(), Self::Error> {
+            self.push(Item::CommentChar { c });
+            Ok(())
+        }
+
+        fn comment_end(&mut self) -> Result<(), Self::Error> {
+            self.push(Item::CommentEnd);
+            Ok(())
+        }
+    }
+
+    fn expression<const N: usize>(tokens: [ExpressionToken<i32>; N]) -> Vec<Item> {
+        tokens
+            .into_iter()
+            .map(|token| Item::Expression { token })
+            .collect()
+    }
+
+    fn comment_chars(s: &str) -> impl Iterator<Item = Item> + '_ {
+        s.chars().map(|c| Item::CommentChar { c })
     }
 
     //
@@ -461,6 +1481,17 @@ mod tests {
                     number: Decimal::new(78, 1),
                 },
                 Item::End,
+                Item::CommentStart {
+                    kind: CommentKind::ControlOut,
+                },
+                Item::CommentChar { c: ' ' },
+                Item::CommentChar { c: '.' },
+                Item::CommentChar { c: ' ' },
+                Item::CommentChar { c: '1' },
+                Item::CommentChar { c: ' ' },
+                Item::CommentChar { c: '1' },
+                Item::CommentChar { c: ' ' },
+                Item::CommentEnd,
                 Item::GCode {
                     number: Decimal::new(1012, 0),
                 },
@@ -476,49 +1507,289 @@ mod tests {
     }
 
     //
-    // BlockParser, state = Start
+    // BlockParser, comments
     //
 
     #[test]
-    fn block_parser_start() {
+    fn block_parser_control_out() {
         let mut block_parser: BlockParser<i32> = BlockParser::default();
         let mut vec = Vec::new();
-        block_parser.try_feed_char('%', &mut vec).unwrap();
-        assert_eq!(vec, vec![Item::Start]);
+        block_parser
+            .try_feed_str("G1 (X2 ; Y3\n Z4) F5\n", &mut vec)
+            .unwrap();
+        let mut expected = vec![Item::CommentStart {
+            kind: CommentKind::ControlOut,
+        }];
+        expected.extend(comment_chars("X2 ; Y3\n Z4"));
+        expected.extend([
+            Item::CommentEnd,
+            Item::GCode {
+                number: Decimal::new(1, 0),
+            },
+            Item::Data {
+                address: 'F',
+                index: None,
+                number: Decimal::new(5, 0),
+            },
+            Item::End,
+        ]);
+        assert_eq!(vec, expected);
     }
 
     #[test]
-    fn block_parser_start_try_end() {
+    fn block_parser_control_out_order() {
+        // The comment can split a word, so it is reported before the word that precedes it.
         let mut block_parser: BlockParser<i32> = BlockParser::default();
         let mut vec = Vec::new();
-        block_parser.try_end(&mut vec).unwrap();
-        assert_eq!(vec, vec![]);
+        block_parser
+            .try_feed_str("X1 (c) Y2\nG1 (c) 0\n", &mut vec)
+            .unwrap();
+        let comment = || {
+            [
+                Item::CommentStart {
+                    kind: CommentKind::ControlOut,
+                },
+                Item::CommentChar { c: 'c' },
+                Item::CommentEnd,
+            ]
+        };
+        let mut expected = Vec::from(comment());
+        expected.extend([
+            Item::Data {
+                address: 'X',
+                index: None,
+                number: Decimal::new(1, 0),
+            },
+            Item::Data {
+                address: 'Y',
+                index: None,
+                number: Decimal::new(2, 0),
+            },
+            Item::End,
+        ]);
+        expected.extend(comment());
+        expected.extend([
+            Item::GCode {
+                number: Decimal::new(10, 0),
+            },
+            Item::End,
+        ]);
+        assert_eq!(vec, expected);
     }
 
-    //
-    // BlockParser, state = BlockSkip
-    //
-
     #[test]
-    fn block_parser_block_skip() {
-        let mut block_parser: BlockParser<i32> = BlockParser::default();
+    fn block_parser_semicolon_order() {
+        let config = Config::new()
+            .with_semicolon_comments(true)
+            .with_o_words(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
         let mut vec = Vec::new();
         block_parser
-            .try_feed_str("G1\n/G2\nG3\n", &mut vec)
+            .try_feed_str("N10;a\nY2=3;b\nO1 sub;c\n", &mut vec)
             .unwrap();
+        let comment = |c| {
+            [
+                Item::CommentStart {
+                    kind: CommentKind::Semicolon,
+                },
+                Item::CommentChar { c },
+                Item::CommentEnd,
+            ]
+        };
+        let mut expected = vec![Item::SequenceNumber {
+            alignment: false,
+            number: Decimal::new(10, 0),
+        }];
+        expected.extend(comment('a'));
+        expected.extend([
+            Item::End,
+            Item::Data {
+                address: 'Y',
+                index: Some(2),
+                number: Decimal::new(3, 0),
+            },
+        ]);
+        expected.extend(comment('b'));
+        expected.extend([
+            Item::End,
+            Item::OWord {
+                number: 1,
+                keyword: OWordKeyword::Sub,
+            },
+        ]);
+        expected.extend(comment('c'));
+        expected.push(Item::End);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn block_parser_control_out_try_end() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        block_parser.try_feed_str("G1 (X", &mut vec).unwrap();
         block_parser.try_end(&mut vec).unwrap();
         assert_eq!(
             vec,
             vec![
+                Item::CommentStart {
+                    kind: CommentKind::ControlOut,
+                },
+                Item::CommentChar { c: 'X' },
+                Item::CommentEnd,
                 Item::GCode {
-                    number: Decimal::new(1, 0)
+                    number: Decimal::new(1, 0),
                 },
                 Item::End,
+            ]
+        );
+        block_parser.try_feed_str("G2\n", &mut vec).unwrap();
+        assert_eq!(
+            vec[5..],
+            [
                 Item::GCode {
-                    number: Decimal::new(3, 0)
+                    number: Decimal::new(2, 0),
                 },
                 Item::End,
-            ],
+            ]
+        );
+    }
+
+    #[test]
+    fn block_parser_semicolon_disabled() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("G1 ;X2\n", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn block_parser_semicolon() {
+        let config = Config::new().with_semicolon_comments(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_str(";LAYER:1\nG1 X2 ; (Y3\nZ4\n", &mut vec)
+            .unwrap();
+        let mut expected = vec![Item::CommentStart {
+            kind: CommentKind::Semicolon,
+        }];
+        expected.extend(comment_chars("LAYER:1"));
+        expected.extend([
+            Item::CommentEnd,
+            Item::GCode {
+                number: Decimal::new(1, 0),
+            },
+            Item::Data {
+                address: 'X',
+                index: None,
+                number: Decimal::new(2, 0),
+            },
+            Item::CommentStart {
+                kind: CommentKind::Semicolon,
+            },
+        ]);
+        expected.extend(comment_chars(" (Y3"));
+        expected.extend([
+            Item::CommentEnd,
+            Item::End,
+            Item::Data {
+                address: 'Z',
+                index: None,
+                number: Decimal::new(4, 0),
+            },
+            Item::End,
+        ]);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn block_parser_semicolon_try_end() {
+        let config = Config::new().with_semicolon_comments(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser.try_feed_str(";M", &mut vec).unwrap();
+        block_parser.try_end(&mut vec).unwrap();
+        assert_eq!(
+            vec,
+            vec![
+                Item::CommentStart {
+                    kind: CommentKind::Semicolon,
+                },
+                Item::CommentChar { c: 'M' },
+                Item::CommentEnd,
+            ]
+        );
+    }
+
+    #[test]
+    fn block_parser_semicolon_in_control_out() {
+        let config = Config::new().with_semicolon_comments(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser.try_feed_str("(;) G1\n", &mut vec).unwrap();
+        assert_eq!(
+            vec,
+            vec![
+                Item::CommentStart {
+                    kind: CommentKind::ControlOut,
+                },
+                Item::CommentChar { c: ';' },
+                Item::CommentEnd,
+                Item::GCode {
+                    number: Decimal::new(1, 0),
+                },
+                Item::End,
+            ]
+        );
+    }
+
+    //
+    // BlockParser, state = Start
+    //
+
+    #[test]
+    fn block_parser_start() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        block_parser.try_feed_char('%', &mut vec).unwrap();
+        assert_eq!(vec, vec![Item::Start]);
+    }
+
+    #[test]
+    fn block_parser_start_try_end() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        block_parser.try_end(&mut vec).unwrap();
+        assert_eq!(vec, vec![]);
+    }
+
+    //
+    // BlockParser, state = BlockSkip
+    //
+
+    #[test]
+    fn block_parser_block_skip() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_str("G1\n/G2\nG3\n", &mut vec)
+            .unwrap();
+        block_parser.try_end(&mut vec).unwrap();
+        assert_eq!(
+            vec,
+            vec![
+                Item::GCode {
+                    number: Decimal::new(1, 0)
+                },
+                Item::End,
+                Item::GCode {
+                    number: Decimal::new(3, 0)
+                },
+                Item::End,
+            ],
         );
     }
 
@@ -559,7 +1830,7 @@ mod tests {
         let mut block_parser: BlockParser<i32> = BlockParser::default();
         let mut vec = Vec::new();
         let result = block_parser.try_feed_str("J2147483648", &mut vec);
-        assert_eq!(result, Err(Error::new_capacity()));
+        assert_eq!(result.map_err(|error| error.kind), Err(ErrorKind::Capacity));
         assert_eq!(vec, vec![]);
     }
 
@@ -569,7 +1840,10 @@ mod tests {
         let mut vec = Vec::new();
         block_parser.try_feed_str("V", &mut vec).unwrap();
         let result = block_parser.try_end(&mut vec);
-        assert_eq!(result, Err(Error::new_incomplete()));
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::Incomplete)
+        );
         assert_eq!(vec, vec![]);
     }
 
@@ -581,39 +1855,1345 @@ mod tests {
         let mut block_parser: BlockParser<i32> = BlockParser::default();
         let mut vec = Vec::new();
         let result = block_parser.try_feed_str("Q,", &mut vec);
-        assert_eq!(result, Err(Error::new_incomplete()));
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::Incomplete)
+        );
         assert_eq!(vec, vec![]);
     }
 
     //
-    // BlockParser, state = Decimal
+    // BlockParser, state = Parameter
     //
 
     #[test]
-    fn block_parser_decimal_capacity() {
+    fn block_parser_parameter_disabled() {
         let mut block_parser: BlockParser<i32> = BlockParser::default();
         let mut vec = Vec::new();
-        let result = block_parser.try_feed_str("S3=2147483648", &mut vec);
-        assert_eq!(result, Err(Error::new_capacity()));
+        let result = block_parser.try_feed_str("X#1", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::Incomplete)
+        );
+    }
+
+    #[test]
+    fn block_parser_parameter() {
+        let config = Config::new().with_parameters(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_str("#5=-12.5 G#1 X#2Y3=#4 #6=1\nZ#7", &mut vec)
+            .unwrap();
+        block_parser.try_end(&mut vec).unwrap();
+        assert_eq!(
+            vec,
+            vec![
+                Item::ParameterAssignment {
+                    parameter: 5,
+                    number: Decimal::new(-125, 1),
+                },
+                Item::ParameterWord {
+                    address: 'G',
+                    index: None,
+                    parameter: 1,
+                },
+                Item::ParameterWord {
+                    address: 'X',
+                    index: None,
+                    parameter: 2,
+                },
+                Item::ParameterWord {
+                    address: 'Y',
+                    index: Some(3),
+                    parameter: 4,
+                },
+                Item::ParameterAssignment {
+                    parameter: 6,
+                    number: Decimal::new(1, 0),
+                },
+                Item::End,
+                Item::ParameterWord {
+                    address: 'Z',
+                    index: None,
+                    parameter: 7,
+                },
+                Item::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn block_parser_parameter_after_word() {
+        let config = Config::new().with_parameters(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser.try_feed_str("X1#2=3\n", &mut vec).unwrap();
+        assert_eq!(
+            vec,
+            vec![
+                Item::Data {
+                    address: 'X',
+                    index: None,
+                    number: Decimal::new(1, 0),
+                },
+                Item::ParameterAssignment {
+                    parameter: 2,
+                    number: Decimal::new(3, 0),
+                },
+                Item::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn block_parser_parameter_assignment_incomplete() {
+        let config = Config::new().with_parameters(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser.try_feed_str("#3", &mut vec).unwrap();
+        let result = block_parser.try_end(&mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::Incomplete)
+        );
         assert_eq!(vec, vec![]);
     }
 
     #[test]
-    fn block_parser_decimal_incomplete() {
+    fn block_parser_parameter_assignment_invalid_character() {
+        let config = Config::new().with_parameters(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("#3.", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+        assert_eq!(vec, vec![]);
+    }
+
+    #[test]
+    fn block_parser_parameter_word_incomplete() {
+        let config = Config::new().with_parameters(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("X#Y", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::Incomplete)
+        );
+        assert_eq!(vec, vec![]);
+    }
+
+    #[test]
+    fn block_parser_parameter_sequence_number() {
+        let config = Config::new().with_parameters(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("N#1", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+        assert_eq!(vec, vec![]);
+    }
+
+    //
+    // BlockParser, state = Expression
+    //
+
+    #[test]
+    fn block_parser_expression_disabled() {
         let mut block_parser: BlockParser<i32> = BlockParser::default();
         let mut vec = Vec::new();
-        block_parser.try_feed_str("I2=.", &mut vec).unwrap();
+        let result = block_parser.try_feed_str("X[1]", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::Incomplete)
+        );
+    }
+
+    #[test]
+    fn block_parser_expression_precedence() {
+        let config = Config::new().with_expressions(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_str("X[1 + 2 * 3 - 4]Y5\n", &mut vec)
+            .unwrap();
+        let mut expected = expression([
+            ExpressionToken::Open,
+            ExpressionToken::Number(Decimal::new(1, 0)),
+            ExpressionToken::Operator(BinaryOperator::Add),
+            ExpressionToken::Number(Decimal::new(2, 0)),
+            ExpressionToken::Operator(BinaryOperator::Multiply),
+            ExpressionToken::Number(Decimal::new(3, 0)),
+            ExpressionToken::ApplyOperator(BinaryOperator::Multiply),
+            ExpressionToken::ApplyOperator(BinaryOperator::Add),
+            ExpressionToken::Operator(BinaryOperator::Subtract),
+            ExpressionToken::Number(Decimal::new(4, 0)),
+            ExpressionToken::ApplyOperator(BinaryOperator::Subtract),
+            ExpressionToken::Close,
+        ]);
+        expected.extend([
+            Item::ExpressionWord {
+                address: 'X',
+                index: None,
+            },
+            Item::Data {
+                address: 'Y',
+                index: None,
+                number: Decimal::new(5, 0),
+            },
+            Item::End,
+        ]);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn block_parser_expression_brackets() {
+        let config = Config::new().with_expressions(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_str("G[[#1 + -.5] ** 2 * 3]\n", &mut vec)
+            .unwrap();
+        let mut expected = expression([
+            ExpressionToken::Open,
+            ExpressionToken::Open,
+            ExpressionToken::Parameter(1),
+            ExpressionToken::Operator(BinaryOperator::Add),
+            ExpressionToken::Number(Decimal::new(-5, 1)),
+            ExpressionToken::ApplyOperator(BinaryOperator::Add),
+            ExpressionToken::Close,
+            ExpressionToken::Operator(BinaryOperator::Power),
+            ExpressionToken::Number(Decimal::new(2, 0)),
+            ExpressionToken::ApplyOperator(BinaryOperator::Power),
+            ExpressionToken::Operator(BinaryOperator::Multiply),
+            ExpressionToken::Number(Decimal::new(3, 0)),
+            ExpressionToken::ApplyOperator(BinaryOperator::Multiply),
+            ExpressionToken::Close,
+        ]);
+        expected.extend([
+            Item::ExpressionWord {
+                address: 'G',
+                index: None,
+            },
+            Item::End,
+        ]);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn block_parser_expression_functions() {
+        let config = Config::new().with_expressions(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_str("Y2=[cos[30] * ATAN[1]/[2]]\n", &mut vec)
+            .unwrap();
+        let mut expected = expression([
+            ExpressionToken::Open,
+            ExpressionToken::Function(Function::Cos),
+            ExpressionToken::Open,
+            ExpressionToken::Number(Decimal::new(30, 0)),
+            ExpressionToken::Close,
+            ExpressionToken::ApplyFunction(Function::Cos),
+            ExpressionToken::Operator(BinaryOperator::Multiply),
+            ExpressionToken::Function(Function::Atan),
+            ExpressionToken::Open,
+            ExpressionToken::Number(Decimal::new(1, 0)),
+            ExpressionToken::Close,
+            ExpressionToken::Operator(BinaryOperator::Divide),
+            ExpressionToken::Open,
+            ExpressionToken::Number(Decimal::new(2, 0)),
+            ExpressionToken::Close,
+            ExpressionToken::ApplyFunction(Function::Atan),
+            ExpressionToken::ApplyOperator(BinaryOperator::Multiply),
+            ExpressionToken::Close,
+        ]);
+        expected.extend([
+            Item::ExpressionWord {
+                address: 'Y',
+                index: Some(2),
+            },
+            Item::End,
+        ]);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn block_parser_expression_negate() {
+        let config = Config::new().with_parameters(true).with_expressions(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_str("X[-#1 ** 2 * -[1 + 2] - -COS[0] + +#2]\n", &mut vec)
+            .unwrap();
+        let mut expected = expression([
+            ExpressionToken::Open,
+            ExpressionToken::Negate,
+            ExpressionToken::Parameter(1),
+            ExpressionToken::ApplyNegate,
+            ExpressionToken::Operator(BinaryOperator::Power),
+            ExpressionToken::Number(Decimal::new(2, 0)),
+            ExpressionToken::ApplyOperator(BinaryOperator::Power),
+            ExpressionToken::Operator(BinaryOperator::Multiply),
+            ExpressionToken::Negate,
+            ExpressionToken::Open,
+            ExpressionToken::Number(Decimal::new(1, 0)),
+            ExpressionToken::Operator(BinaryOperator::Add),
+            ExpressionToken::Number(Decimal::new(2, 0)),
+            ExpressionToken::ApplyOperator(BinaryOperator::Add),
+            ExpressionToken::Close,
+            ExpressionToken::ApplyNegate,
+            ExpressionToken::ApplyOperator(BinaryOperator::Multiply),
+            ExpressionToken::Operator(BinaryOperator::Subtract),
+            ExpressionToken::Negate,
+            ExpressionToken::Function(Function::Cos),
+            ExpressionToken::Open,
+            ExpressionToken::Number(Decimal::new(0, 0)),
+            ExpressionToken::Close,
+            ExpressionToken::ApplyFunction(Function::Cos),
+            ExpressionToken::ApplyNegate,
+            ExpressionToken::ApplyOperator(BinaryOperator::Subtract),
+            ExpressionToken::Operator(BinaryOperator::Add),
+            ExpressionToken::Parameter(2),
+            ExpressionToken::ApplyOperator(BinaryOperator::Add),
+            ExpressionToken::Close,
+        ]);
+        expected.extend([
+            Item::ExpressionWord {
+                address: 'X',
+                index: None,
+            },
+            Item::End,
+        ]);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn block_parser_expression_negate_number() {
+        let config = Config::new().with_expressions(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser.try_feed_str("X[--1.5]\n", &mut vec).unwrap();
+        let mut expected = expression([
+            ExpressionToken::Open,
+            ExpressionToken::Negate,
+            ExpressionToken::Number(Decimal::new(-15, 1)),
+            ExpressionToken::ApplyNegate,
+            ExpressionToken::Close,
+        ]);
+        expected.extend([
+            Item::ExpressionWord {
+                address: 'X',
+                index: None,
+            },
+            Item::End,
+        ]);
+        assert_eq!(vec, expected);
+        let result = block_parser.try_feed_str("X[-]", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn block_parser_expression_operator_names() {
+        let config = Config::new().with_parameters(true).with_expressions(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_str("#3=[1 MOD 2 GT 0 and abs[1]]\n", &mut vec)
+            .unwrap();
+        let mut expected = expression([
+            ExpressionToken::Open,
+            ExpressionToken::Number(Decimal::new(1, 0)),
+            ExpressionToken::Operator(BinaryOperator::Modulo),
+            ExpressionToken::Number(Decimal::new(2, 0)),
+            ExpressionToken::ApplyOperator(BinaryOperator::Modulo),
+            ExpressionToken::Operator(BinaryOperator::Greater),
+            ExpressionToken::Number(Decimal::new(0, 0)),
+            ExpressionToken::ApplyOperator(BinaryOperator::Greater),
+            ExpressionToken::Operator(BinaryOperator::And),
+            ExpressionToken::Function(Function::Abs),
+            ExpressionToken::Open,
+            ExpressionToken::Number(Decimal::new(1, 0)),
+            ExpressionToken::Close,
+            ExpressionToken::ApplyFunction(Function::Abs),
+            ExpressionToken::ApplyOperator(BinaryOperator::And),
+            ExpressionToken::Close,
+        ]);
+        expected.extend([Item::ExpressionAssignment { parameter: 3 }, Item::End]);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn block_parser_expression_capacity() {
+        let config = Config::new().with_expressions(true);
+        let mut block_parser: BlockParser<i32, i32, i32, 2> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("X[1+[2]]", &mut vec);
+        assert_eq!(result.map_err(|error| error.kind), Err(ErrorKind::Capacity));
+    }
+
+    #[test]
+    fn block_parser_expression_incomplete() {
+        let config = Config::new().with_expressions(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser.try_feed_str("X[1+", &mut vec).unwrap();
         let result = block_parser.try_end(&mut vec);
-        assert_eq!(result, Err(Error::new_incomplete()));
-        assert_eq!(vec, vec![]);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::Incomplete)
+        );
     }
 
     #[test]
-    fn block_parser_decimal_invalid_character() {
+    fn block_parser_expression_invalid_function() {
+        let config = Config::new().with_expressions(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("X[FOO[1]]", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn block_parser_expression_invalid_operator() {
+        let config = Config::new().with_expressions(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("X[1 = 2]", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn block_parser_expression_sequence_number() {
+        let config = Config::new().with_expressions(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("N[1]", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+    }
+
+    //
+    // BlockParser, state = OWord
+    //
+
+    #[test]
+    fn block_parser_o_word_disabled() {
         let mut block_parser: BlockParser<i32> = BlockParser::default();
         let mut vec = Vec::new();
-        let result = block_parser.try_feed_str("T6=5+", &mut vec);
-        assert_eq!(result, Err(Error::new_invalid_character()));
+        block_parser.try_feed_str("O100\n", &mut vec).unwrap();
+        assert_eq!(
+            vec,
+            vec![
+                Item::Data {
+                    address: 'O',
+                    index: None,
+                    number: Decimal::new(100, 0),
+                },
+                Item::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn block_parser_o_word() {
+        let config = Config::new().with_o_words(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_str(
+                "o100 sub\nN2 O100 ENDSUB\nO101 call [1] [#2]\no102 endwhile",
+                &mut vec,
+            )
+            .unwrap();
+        block_parser.try_end(&mut vec).unwrap();
+        let mut expected = vec![
+            Item::OWord {
+                number: 100,
+                keyword: OWordKeyword::Sub,
+            },
+            Item::End,
+            Item::SequenceNumber {
+                alignment: false,
+                number: Decimal::new(2, 0),
+            },
+            Item::OWord {
+                number: 100,
+                keyword: OWordKeyword::EndSub,
+            },
+            Item::End,
+            Item::OWord {
+                number: 101,
+                keyword: OWordKeyword::Call,
+            },
+        ];
+        expected.extend(expression([
+            ExpressionToken::Open,
+            ExpressionToken::Number(Decimal::new(1, 0)),
+            ExpressionToken::Close,
+        ]));
+        expected.push(Item::OWordArgument);
+        expected.extend(expression([
+            ExpressionToken::Open,
+            ExpressionToken::Parameter(2),
+            ExpressionToken::Close,
+        ]));
+        expected.extend([
+            Item::OWordArgument,
+            Item::End,
+            Item::OWord {
+                number: 102,
+                keyword: OWordKeyword::EndWhile,
+            },
+            Item::End,
+        ]);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn block_parser_o_word_comment() {
+        let config = Config::new().with_o_words(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_str("O1 else (x)\n", &mut vec)
+            .unwrap();
+        let mut expected = vec![Item::CommentStart {
+            kind: CommentKind::ControlOut,
+        }];
+        expected.extend(comment_chars("x"));
+        expected.extend([
+            Item::CommentEnd,
+            Item::OWord {
+                number: 1,
+                keyword: OWordKeyword::Else,
+            },
+            Item::End,
+        ]);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn block_parser_o_word_incomplete() {
+        let config = Config::new().with_o_words(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser.try_feed_str("O", &mut vec).unwrap();
+        let result = block_parser.try_end(&mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::Incomplete)
+        );
+    }
+
+    #[test]
+    fn block_parser_o_word_missing_keyword() {
+        let config = Config::new().with_o_words(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("O1 [1]", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn block_parser_o_word_invalid_keyword() {
+        let config = Config::new().with_o_words(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("O1 goto\n", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+        assert_eq!(vec, vec![]);
+    }
+
+    #[test]
+    fn block_parser_o_word_keyword_too_long() {
+        let config = Config::new().with_o_words(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("O1 endrepeats", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn block_parser_o_word_trailing_word() {
+        let config = Config::new().with_o_words(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("O1 if [1] X2", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+    }
+
+    //
+    // BlockParser, index and sequence number types
+    //
+
+    /// Records the addresses and values of the index and sequence number types (e.g., `('N', 1)`
+    /// for `N1`.)
+    struct Indexes(Vec<(char, u32)>);
+
+    impl BlockBuilder for Indexes {
+        type Error = ();
+        type Significand = i16;
+        type Index = u16;
+        type SequenceNumber = u32;
+
+        fn program_start(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn sequence_number(&mut self, _: bool, number: Decimal<u32>) -> Result<(), Self::Error> {
+            self.0.push(('N', number.significand()));
+            Ok(())
+        }
+
+        fn g_code(&mut self, _: Decimal<i16>) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn m_code(&mut self, _: Decimal<i16>) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn data(
+            &mut self,
+            address: char,
+            index: Option<u16>,
+            _: Decimal<i16>,
+        ) -> Result<(), Self::Error> {
+            self.0.extend(index.map(|index| (address, index.into())));
+            Ok(())
+        }
+
+        fn parameter_word(
+            &mut self,
+            address: char,
+            _: Option<u16>,
+            parameter: u16,
+        ) -> Result<(), Self::Error> {
+            self.0.push((address, parameter.into()));
+            Ok(())
+        }
+
+        fn parameter_assignment(
+            &mut self,
+            parameter: u16,
+            _: Decimal<i16>,
+        ) -> Result<(), Self::Error> {
+            self.0.push(('#', parameter.into()));
+            Ok(())
+        }
+
+        fn expression(&mut self, token: ExpressionToken<i16, u16>) -> Result<(), Self::Error> {
+            if let ExpressionToken::Parameter(parameter) = token {
+                self.0.push(('[', parameter.into()));
+            }
+            Ok(())
+        }
+
+        fn expression_word(&mut self, _: char, _: Option<u16>) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn expression_assignment(&mut self, _: u16) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn o_word(&mut self, number: u16, _: OWordKeyword) -> Result<(), Self::Error> {
+            self.0.push(('O', number.into()));
+            Ok(())
+        }
+
+        fn o_word_argument(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn checksum(&mut self, _: u8, _: bool) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn end_block(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn abort_block(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn block_parser_index_type() {
+        let config = Config::new()
+            .with_parameters(true)
+            .with_expressions(true)
+            .with_o_words(true);
+        let mut block_parser: BlockParser<i16, u16, u32> = BlockParser::new(config);
+        let mut indexes = Indexes(Vec::new());
+        block_parser
+            .try_feed_str(
+                "N4000000000 X3=1 Y#60000 #60001=[#60002]\nO60003 endif\n",
+                &mut indexes,
+            )
+            .unwrap();
+        assert_eq!(
+            indexes.0,
+            vec![
+                ('N', 4_000_000_000),
+                ('X', 3),
+                ('Y', 60000),
+                ('[', 60002),
+                ('O', 60003),
+            ]
+        );
+    }
+
+    #[test]
+    fn block_parser_index_type_negative() {
+        let mut block_parser: BlockParser<i16, u16, u32> = BlockParser::default();
+        let mut indexes = Indexes(Vec::new());
+        let result = block_parser.try_feed_str("N-1 G1\n", &mut indexes);
+        assert_eq!(result.map_err(|error| error.kind), Err(ErrorKind::Capacity));
+    }
+
+    #[test]
+    fn block_parser_index_type_parameter_capacity() {
+        let config = Config::new().with_parameters(true);
+        let mut block_parser: BlockParser<i16, u16, u32> = BlockParser::new(config);
+        let mut indexes = Indexes(Vec::new());
+        let result = block_parser.try_feed_str("#70000=1\n", &mut indexes);
+        assert_eq!(result.map_err(|error| error.kind), Err(ErrorKind::Capacity));
+    }
+
+    #[test]
+    fn block_parser_index_type_capacity() {
+        let mut block_parser: BlockParser<i16, u16, u32> = BlockParser::default();
+        let mut indexes = Indexes(Vec::new());
+        let result = block_parser.try_feed_str("X40000=1\n", &mut indexes);
+        assert_eq!(result.map_err(|error| error.kind), Err(ErrorKind::Capacity));
+    }
+
+    //
+    // BlockParser, bytes
+    //
+
+    #[test]
+    fn block_parser_bytes() {
+        let s = "N1 G1 (X2) Y3\n";
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut expected = Vec::new();
+        block_parser.try_feed_str(s, &mut expected).unwrap();
+        let mut vec = Vec::new();
+        block_parser.try_feed_bytes(s, &mut vec).unwrap();
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn block_parser_bytes_non_ascii_comment() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_bytes(b"(90\xb0)\n", &mut vec)
+            .unwrap();
+        let mut expected = vec![Item::CommentStart {
+            kind: CommentKind::ControlOut,
+        }];
+        expected.extend(comment_chars("90\u{b0}"));
+        expected.push(Item::CommentEnd);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn block_parser_bytes_ascii_comments() {
+        let config = Config::new().with_ascii_comments(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_bytes(b"(90\xb0)\n", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn block_parser_bytes_non_ascii_word() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_bytes(b"G1 \xb0\n", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn block_parser_bytes_checksum() {
+        let config = Config::new().with_checksums(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_bytes(b"M105(\xb0)*200\n", &mut vec)
+            .unwrap();
+        assert_eq!(
+            vec.last_chunk::<2>(),
+            Some(&[
+                Item::Checksum {
+                    checksum: 200,
+                    valid: true,
+                },
+                Item::End,
+            ])
+        );
+    }
+
+    //
+    // BlockParser, recovery
+    //
+
+    fn feed_recovering(
+        block_parser: &mut BlockParser<i32>,
+        s: &str,
+        vec: &mut Vec<Item>,
+    ) -> Vec<ErrorKind<()>> {
+        s.chars()
+            .filter_map(|c| block_parser.try_feed_char(c, vec).err())
+            .map(|error| error.kind)
+            .collect()
+    }
+
+    #[test]
+    fn block_parser_recovery() {
+        let config = Config::new().with_recovery(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let errors = feed_recovering(&mut block_parser, "G1 X! Y2 (\n\nG2\n", &mut vec);
+        assert_eq!(errors, vec![ErrorKind::Incomplete]);
+        assert_eq!(
+            vec,
+            vec![
+                Item::GCode {
+                    number: Decimal::new(1, 0),
+                },
+                Item::Abort,
+                Item::GCode {
+                    number: Decimal::new(2, 0),
+                },
+                Item::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn block_parser_recovery_line_feed() {
+        let config = Config::new().with_recovery(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let errors = feed_recovering(&mut block_parser, "G1 X\nG2\n", &mut vec);
+        assert_eq!(errors, vec![ErrorKind::Incomplete]);
+        assert_eq!(
+            vec,
+            vec![
+                Item::GCode {
+                    number: Decimal::new(1, 0),
+                },
+                Item::Abort,
+                Item::GCode {
+                    number: Decimal::new(2, 0),
+                },
+                Item::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn block_parser_recovery_try_end() {
+        let config = Config::new().with_recovery(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser.try_feed_str("G1 X", &mut vec).unwrap();
+        let result = block_parser.try_end(&mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::Incomplete)
+        );
+        block_parser.try_feed_str("G2\n", &mut vec).unwrap();
+        assert_eq!(
+            vec,
+            vec![
+                Item::GCode {
+                    number: Decimal::new(1, 0),
+                },
+                Item::Abort,
+                Item::GCode {
+                    number: Decimal::new(2, 0),
+                },
+                Item::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn block_parser_recovery_checksum() {
+        let config = Config::new().with_checksums(true).with_recovery(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let errors = feed_recovering(&mut block_parser, "G1 X!\nM105*121\n", &mut vec);
+        assert_eq!(errors, vec![ErrorKind::Incomplete]);
+        assert_eq!(
+            vec.last_chunk::<2>(),
+            Some(&[
+                Item::Checksum {
+                    checksum: 121,
+                    valid: true,
+                },
+                Item::End,
+            ])
+        );
+    }
+
+    //
+    // BlockParser, unique words
+    //
+
+    #[test]
+    fn block_parser_unique_words() {
+        let config = Config::new().with_unique_words(true).with_recovery(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let errors = feed_recovering(&mut block_parser, "G1 X1 x2 Y3\nG1 G2 X1\n", &mut vec);
+        assert_eq!(errors, vec![ErrorKind::DuplicateWord]);
+        assert_eq!(
+            vec,
+            vec![
+                Item::GCode {
+                    number: Decimal::new(1, 0),
+                },
+                Item::Data {
+                    address: 'X',
+                    index: None,
+                    number: Decimal::new(1, 0),
+                },
+                Item::Abort,
+                Item::GCode {
+                    number: Decimal::new(1, 0),
+                },
+                Item::GCode {
+                    number: Decimal::new(2, 0),
+                },
+                Item::Data {
+                    address: 'X',
+                    index: None,
+                    number: Decimal::new(1, 0),
+                },
+                Item::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn block_parser_unique_words_index() {
+        let config = Config::new().with_unique_words(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_str("P1 P1=2 P2=2 Q31=1\n", &mut vec)
+            .unwrap();
+        let result = block_parser.try_feed_str("P2=3\n", &mut vec);
+        assert!(result.is_ok());
+        let result = block_parser.try_feed_str("P2=1 P2=3\n", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::DuplicateWord)
+        );
+    }
+
+    #[test]
+    fn block_parser_unique_words_large_index() {
+        let config = Config::new().with_unique_words(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("Q40=1 Q40=1\n", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::UnsupportedIndex)
+        );
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        block_parser
+            .try_feed_str("Q40=1 Q40=1\n", &mut vec)
+            .unwrap();
+    }
+
+    #[test]
+    fn block_parser_unique_words_values() {
+        let config = Config::new()
+            .with_unique_words(true)
+            .with_parameters(true)
+            .with_expressions(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("X#1 X[2]\n", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::DuplicateWord)
+        );
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let result = block_parser.try_feed_str("G#1 G#1 #1=2 #1=3 Y[1] Y#2\n", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::DuplicateWord)
+        );
+    }
+
+    #[test]
+    fn block_parser_duplicate_words() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        block_parser.try_feed_str("X1 X2\n", &mut vec).unwrap();
+        assert_eq!(vec.len(), 3);
+    }
+
+    //
+    // BlockParser, position
+    //
+
+    #[cfg(feature = "position")]
+    #[test]
+    fn block_parser_position_error() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("G1\nX1 Y\u{b0}", &mut vec);
+        let error = result.unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Incomplete);
+        assert_eq!(error.position().line(), 1);
+        assert_eq!(error.position().column(), 4);
+        assert_eq!(error.position().offset(), 7);
+        assert_eq!(block_parser.position().offset(), 9);
+    }
+
+    #[cfg(feature = "position")]
+    #[test]
+    fn block_parser_position_bytes() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_bytes(b"(\xb0)\nG1", &mut vec)
+            .unwrap();
+        assert_eq!(block_parser.position().line(), 1);
+        assert_eq!(block_parser.position().column(), 2);
+        assert_eq!(block_parser.position().offset(), 6);
+    }
+
+    #[cfg(feature = "position")]
+    #[test]
+    fn block_parser_position_try_end() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        block_parser.try_feed_str("G1 X", &mut vec).unwrap();
+        let error = block_parser.try_end(&mut vec).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Incomplete);
+        assert_eq!(error.position().line(), 0);
+        assert_eq!(error.position().column(), 4);
+    }
+
+    #[cfg(feature = "position")]
+    #[test]
+    fn block_parser_position_word_start() {
+        struct WordStarts(Vec<usize>);
+
+        impl BlockBuilder for WordStarts {
+            type Error = ();
+            type Significand = i32;
+
+            fn program_start(&mut self) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn sequence_number(&mut self, _: bool, _: Decimal<i32>) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn g_code(&mut self, _: Decimal<i32>) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn m_code(&mut self, _: Decimal<i32>) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn data(
+                &mut self,
+                _: char,
+                _: Option<i32>,
+                _: Decimal<i32>,
+            ) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn parameter_word(
+                &mut self,
+                _: char,
+                _: Option<i32>,
+                _: i32,
+            ) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn parameter_assignment(&mut self, _: i32, _: Decimal<i32>) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn expression(&mut self, _: ExpressionToken<i32>) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn expression_word(&mut self, _: char, _: Option<i32>) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn expression_assignment(&mut self, _: i32) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn o_word(&mut self, _: i32, _: OWordKeyword) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn o_word_argument(&mut self) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn checksum(&mut self, _: u8, _: bool) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn end_block(&mut self) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn abort_block(&mut self) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn word_start(&mut self, position: Position) -> Result<(), Self::Error> {
+                self.0.push(position.offset());
+                Ok(())
+            }
+        }
+
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut word_starts = WordStarts(Vec::new());
+        block_parser
+            .try_feed_str("N1 G1 (c) X1\nM2\n", &mut word_starts)
+            .unwrap();
+        assert_eq!(word_starts.0, vec![0, 3, 10, 13]);
+    }
+
+    //
+    // BlockParser, state = Checksum
+    //
+
+    #[test]
+    fn block_parser_checksum_disabled() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("N3 M105*36\n", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn block_parser_checksum() {
+        let config = Config::new().with_checksums(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_str("N123 G1 X1*97\nN3 M105*35\nM105\n", &mut vec)
+            .unwrap();
+        assert_eq!(
+            vec,
+            vec![
+                Item::SequenceNumber {
+                    alignment: false,
+                    number: Decimal::new(123, 0),
+                },
+                Item::GCode {
+                    number: Decimal::new(1, 0),
+                },
+                Item::Data {
+                    address: 'X',
+                    index: None,
+                    number: Decimal::new(1, 0),
+                },
+                Item::Checksum {
+                    checksum: 97,
+                    valid: true,
+                },
+                Item::End,
+                Item::SequenceNumber {
+                    alignment: false,
+                    number: Decimal::new(3, 0),
+                },
+                Item::MCode {
+                    number: Decimal::new(105, 0),
+                },
+                Item::Checksum {
+                    checksum: 35,
+                    valid: false,
+                },
+                Item::End,
+                Item::MCode {
+                    number: Decimal::new(105, 0),
+                },
+                Item::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn block_parser_checksum_comment() {
+        let config = Config::new().with_checksums(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser
+            .try_feed_str("N2 G1 (a*b) X2*104", &mut vec)
+            .unwrap();
+        block_parser.try_end(&mut vec).unwrap();
+        assert_eq!(
+            vec.last_chunk::<2>(),
+            Some(&[
+                Item::Checksum {
+                    checksum: 104,
+                    valid: true,
+                },
+                Item::End,
+            ])
+        );
+    }
+
+    #[test]
+    fn block_parser_checksum_expression() {
+        let config = Config::new().with_expressions(true).with_checksums(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        block_parser.try_feed_str("X[2*3]*117\n", &mut vec).unwrap();
+        let mut expected = expression([
+            ExpressionToken::Open,
+            ExpressionToken::Number(Decimal::new(2, 0)),
+            ExpressionToken::Operator(BinaryOperator::Multiply),
+            ExpressionToken::Number(Decimal::new(3, 0)),
+            ExpressionToken::ApplyOperator(BinaryOperator::Multiply),
+            ExpressionToken::Close,
+        ]);
+        expected.extend([
+            Item::ExpressionWord {
+                address: 'X',
+                index: None,
+            },
+            Item::Checksum {
+                checksum: 117,
+                valid: true,
+            },
+            Item::End,
+        ]);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn block_parser_checksum_incomplete() {
+        let config = Config::new().with_checksums(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("G1*\n", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::Incomplete)
+        );
+    }
+
+    #[test]
+    fn block_parser_checksum_capacity() {
+        let config = Config::new().with_checksums(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("G1*256", &mut vec);
+        assert_eq!(result.map_err(|error| error.kind), Err(ErrorKind::Capacity));
+    }
+
+    #[test]
+    fn block_parser_checksum_trailing_word() {
+        let config = Config::new().with_checksums(true);
+        let mut block_parser: BlockParser<i32> = BlockParser::new(config);
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("G1*12 X1", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
+    }
+
+    //
+    // BlockParser, state = Decimal
+    //
+
+    #[test]
+    fn block_parser_decimal_capacity() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("S3=2147483648", &mut vec);
+        assert_eq!(result.map_err(|error| error.kind), Err(ErrorKind::Capacity));
+        assert_eq!(vec, vec![]);
+    }
+
+    #[test]
+    fn block_parser_decimal_incomplete() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        block_parser.try_feed_str("I2=.", &mut vec).unwrap();
+        let result = block_parser.try_end(&mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::Incomplete)
+        );
+        assert_eq!(vec, vec![]);
+    }
+
+    #[test]
+    fn block_parser_decimal_invalid_character() {
+        let mut block_parser: BlockParser<i32> = BlockParser::default();
+        let mut vec = Vec::new();
+        let result = block_parser.try_feed_str("T6=5+", &mut vec);
+        assert_eq!(
+            result.map_err(|error| error.kind),
+            Err(ErrorKind::InvalidCharacter)
+        );
         assert_eq!(
             vec,
             vec![Item::Data {
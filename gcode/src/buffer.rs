@@ -0,0 +1,67 @@
+use core::fmt::{self, Write};
+use core::str;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// A fixed-capacity text buffer.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Buffer<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for Buffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Buffer<N> {
+    /// Creates a new empty [`Buffer`].
+    pub const fn new() -> Self {
+        Self {
+            bytes: [0; N],
+            len: 0,
+        }
+    }
+
+    /// Returns the text.
+    pub fn as_str(&self) -> &str {
+        // Only whole strings are written, so the text is always valid UTF-8.
+        str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
+
+impl<const N: usize> Write for Buffer<N> {
+    /// Appends a string. Returns an error, and appends nothing, if the buffer is full.
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len.checked_add(s.len()).ok_or(fmt::Error)?;
+        let bytes = self.bytes.get_mut(self.len..end).ok_or(fmt::Error)?;
+        bytes.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_str() {
+        let mut buffer = Buffer::<8>::default();
+        assert_eq!(buffer.write_str("G1"), Ok(()));
+        assert_eq!(buffer.write_str(" X1"), Ok(()));
+        assert_eq!(buffer.as_str(), "G1 X1");
+    }
+
+    #[test]
+    fn write_str_full() {
+        let mut buffer = Buffer::<4>::default();
+        assert_eq!(buffer.write_str("G1"), Ok(()));
+        assert_eq!(buffer.write_str(" X1"), Err(fmt::Error));
+        assert_eq!(buffer.as_str(), "G1");
+    }
+}
@@ -0,0 +1,511 @@
+use core::fmt::{self, Debug};
+
+use crate::block::BlockBuilder;
+use crate::decimal::Decimal;
+use crate::expression::ExpressionToken;
+use crate::o_word::OWordKeyword;
+use crate::significand::Significand;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// The error type for the [`BlockCollector`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum CollectorError {
+    /// The block has more G codes, M codes, or words than the capacity of the [`Block`].
+    Capacity,
+
+    /// A block is complete, but the previous complete block has not been taken with
+    /// [`BlockCollector::take_block`] yet.
+    Pending,
+
+    /// The block has a parameter, an expression, or an O-word. The [`BlockCollector`] does not
+    /// evaluate these.
+    Unsupported,
+}
+
+/// A data word of a [`Block`] (e.g., `X1.5` or `P2=3`.)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Word<S, I = S>
+where
+    S: Significand,
+    I: Significand,
+{
+    address: char,
+    index: Option<I>,
+    number: Decimal<S>,
+}
+
+impl<S, I> Word<S, I>
+where
+    S: Significand,
+    I: Significand,
+{
+    /// Returns the address of the word, in upper case.
+    #[must_use]
+    pub const fn address(&self) -> char {
+        self.address
+    }
+
+    /// Returns the index of the address, if any.
+    #[must_use]
+    pub const fn index(&self) -> Option<I> {
+        self.index
+    }
+
+    /// Returns the value of the word.
+    #[must_use]
+    pub const fn number(&self) -> Decimal<S> {
+        self.number
+    }
+}
+
+/// A complete block, assembled by the [`BlockCollector`].
+///
+/// The G codes, M codes, and data words are stored in the order of the text, in fixed-size arrays
+/// of `CAPACITY` elements each.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Block<S, I = S, N = I, const CAPACITY: usize = 8>
+where
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    sequence_number: Option<Decimal<N>>,
+    g_codes: List<Decimal<S>, CAPACITY>,
+    m_codes: List<Decimal<S>, CAPACITY>,
+    words: List<Word<S, I>, CAPACITY>,
+    checksum_valid: Option<bool>,
+}
+
+impl<S, I, N, const CAPACITY: usize> Block<S, I, N, CAPACITY>
+where
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    fn new() -> Self {
+        let zero = Decimal::new(S::default(), 0);
+        let word = Word {
+            address: 'A',
+            index: None,
+            number: zero,
+        };
+        Self {
+            sequence_number: None,
+            g_codes: List::new(zero),
+            m_codes: List::new(zero),
+            words: List::new(word),
+            checksum_valid: None,
+        }
+    }
+
+    /// Returns the sequence number of the block, if any.
+    #[must_use]
+    pub const fn sequence_number(&self) -> Option<Decimal<N>> {
+        self.sequence_number
+    }
+
+    /// Returns the G codes of the block.
+    #[must_use]
+    pub fn g_codes(&self) -> &[Decimal<S>] {
+        self.g_codes.as_slice()
+    }
+
+    /// Returns the M codes of the block.
+    #[must_use]
+    pub fn m_codes(&self) -> &[Decimal<S>] {
+        self.m_codes.as_slice()
+    }
+
+    /// Returns the data words of the block.
+    #[must_use]
+    pub fn words(&self) -> &[Word<S, I>] {
+        self.words.as_slice()
+    }
+
+    /// Returns the value of the first data word with the address, in either case, and without
+    /// an index.
+    #[must_use]
+    pub fn word(&self, address: char) -> Option<Decimal<S>> {
+        self.words()
+            .iter()
+            .find(|word| word.index.is_none() && word.address.eq_ignore_ascii_case(&address))
+            .map(Word::number)
+    }
+
+    /// Returns `Some(true)` if the block has a valid checksum, `Some(false)` if it has an invalid
+    /// checksum, and `None` if it has no checksum.
+    #[must_use]
+    pub const fn checksum_valid(&self) -> Option<bool> {
+        self.checksum_valid
+    }
+}
+
+/// A [`BlockBuilder`] that assembles each complete [`Block`].
+///
+/// The [`BlockCollector`] holds the most recent complete block until it is taken with
+/// [`BlockCollector::take_block`]. A character completes at most one block, so the caller should
+/// take the block after each character. If another block is complete before the previous one is
+/// taken (e.g., a string of more than one line fed with [`BlockParser::try_feed_str`]), it is
+/// discarded with an error of [`CollectorError::Pending`]. Comments are discarded. A block with a
+/// parameter, an expression, or an O-word produces an error of [`CollectorError::Unsupported`].
+///
+///     use gcode::{BlockCollector, BlockParser, Decimal};
+///
+///     let mut parser = BlockParser::<i32>::default();
+///     let mut collector = BlockCollector::<i32>::default();
+///     for c in "G1 X1.5 Y2\n".chars() {
+///         parser.try_feed_char(c, &mut collector).unwrap();
+///         if let Some(block) = collector.take_block() {
+///             assert_eq!(block.g_codes(), [Decimal::new(1, 0)]);
+///             assert_eq!(block.word('X'), Some(Decimal::new(15, 1)));
+///         }
+///     }
+///
+/// [`BlockParser::try_feed_str`]: crate::BlockParser::try_feed_str
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct BlockCollector<S, I = S, N = I, const CAPACITY: usize = 8>
+where
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    block: Block<S, I, N, CAPACITY>,
+    complete: Option<Block<S, I, N, CAPACITY>>,
+}
+
+impl<S, I, N, const CAPACITY: usize> Default for BlockCollector<S, I, N, CAPACITY>
+where
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S, I, N, const CAPACITY: usize> BlockCollector<S, I, N, CAPACITY>
+where
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    /// Creates a new [`BlockCollector`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            block: Block::new(),
+            complete: None,
+        }
+    }
+
+    /// Takes the most recent complete block, if any.
+    pub const fn take_block(&mut self) -> Option<Block<S, I, N, CAPACITY>> {
+        self.complete.take()
+    }
+}
+
+impl<S, I, N, const CAPACITY: usize> BlockBuilder for BlockCollector<S, I, N, CAPACITY>
+where
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    type Error = CollectorError;
+    type Significand = S;
+    type Index = I;
+    type SequenceNumber = N;
+
+    fn program_start(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn sequence_number(&mut self, alignment: bool, number: Decimal<N>) -> Result<(), Self::Error> {
+        let _ = alignment;
+        self.block.sequence_number = Some(number);
+        Ok(())
+    }
+
+    fn g_code(&mut self, number: Decimal<S>) -> Result<(), Self::Error> {
+        self.block.g_codes.push(number)
+    }
+
+    fn m_code(&mut self, number: Decimal<S>) -> Result<(), Self::Error> {
+        self.block.m_codes.push(number)
+    }
+
+    fn data(
+        &mut self,
+        address: char,
+        index: Option<I>,
+        number: Decimal<S>,
+    ) -> Result<(), Self::Error> {
+        self.block.words.push(Word {
+            address: address.to_ascii_uppercase(),
+            index,
+            number,
+        })
+    }
+
+    fn parameter_word(
+        &mut self,
+        address: char,
+        index: Option<I>,
+        parameter: I,
+    ) -> Result<(), Self::Error> {
+        let _ = (address, index, parameter);
+        Err(CollectorError::Unsupported)
+    }
+
+    fn parameter_assignment(
+        &mut self,
+        parameter: I,
+        number: Decimal<S>,
+    ) -> Result<(), Self::Error> {
+        let _ = (parameter, number);
+        Err(CollectorError::Unsupported)
+    }
+
+    fn expression(&mut self, token: ExpressionToken<S, I>) -> Result<(), Self::Error> {
+        let _ = token;
+        Err(CollectorError::Unsupported)
+    }
+
+    fn expression_word(&mut self, address: char, index: Option<I>) -> Result<(), Self::Error> {
+        let _ = (address, index);
+        Err(CollectorError::Unsupported)
+    }
+
+    fn expression_assignment(&mut self, parameter: I) -> Result<(), Self::Error> {
+        let _ = parameter;
+        Err(CollectorError::Unsupported)
+    }
+
+    fn o_word(&mut self, number: I, keyword: OWordKeyword) -> Result<(), Self::Error> {
+        let _ = (number, keyword);
+        Err(CollectorError::Unsupported)
+    }
+
+    fn o_word_argument(&mut self) -> Result<(), Self::Error> {
+        Err(CollectorError::Unsupported)
+    }
+
+    fn checksum(&mut self, checksum: u8, valid: bool) -> Result<(), Self::Error> {
+        let _ = checksum;
+        self.block.checksum_valid = Some(valid);
+        Ok(())
+    }
+
+    fn end_block(&mut self) -> Result<(), Self::Error> {
+        let block = core::mem::replace(&mut self.block, Block::new());
+        if self.complete.is_some() {
+            return Err(CollectorError::Pending);
+        }
+        self.complete = Some(block);
+        Ok(())
+    }
+
+    fn abort_block(&mut self) -> Result<(), Self::Error> {
+        self.block = Block::new();
+        Ok(())
+    }
+}
+
+/// A fixed-capacity list.
+#[derive(Clone, Copy)]
+struct List<T, const N: usize> {
+    items: [T; N],
+    len: usize,
+}
+
+impl<T, const N: usize> List<T, N>
+where
+    T: Copy,
+{
+    /// Creates an empty list. The `filler` value is never returned.
+    const fn new(filler: T) -> Self {
+        Self {
+            items: [filler; N],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, item: T) -> Result<(), CollectorError> {
+        let slot = self
+            .items
+            .get_mut(self.len)
+            .ok_or(CollectorError::Capacity)?;
+        *slot = item;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn as_slice(&self) -> &[T] {
+        &self.items[..self.len]
+    }
+}
+
+/// Only the items of the list are printed, not the unused slots.
+impl<T, const N: usize> Debug for List<T, N>
+where
+    T: Copy + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<T, const N: usize> Format for List<T, N>
+where
+    T: Copy + Format,
+{
+    fn format(&self, f: defmt::Formatter) {
+        self.as_slice().format(f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::fmt::Write;
+
+    use crate::block::{BlockParser, Error, ErrorKind};
+    use crate::buffer::Buffer;
+    use crate::config::Config;
+
+    #[test]
+    fn block_collector() {
+        let mut parser = BlockParser::<i32, u8, u32>::default();
+        let mut collector = BlockCollector::<i32, u8, u32>::default();
+        parser
+            .try_feed_str("N10 G1 G90 x1.5 Y-2 (move) M3\n", &mut collector)
+            .unwrap();
+        let block = collector.take_block().unwrap();
+        assert_eq!(block.sequence_number(), Some(Decimal::new(10, 0)));
+        assert_eq!(block.g_codes(), [Decimal::new(1, 0), Decimal::new(90, 0)]);
+        assert_eq!(block.m_codes(), [Decimal::new(3, 0)]);
+        assert_eq!(block.words().len(), 2);
+        assert_eq!(block.words()[0].address(), 'X');
+        assert_eq!(block.word('x'), Some(Decimal::new(15, 1)));
+        assert_eq!(block.word('Y'), Some(Decimal::new(-2, 0)));
+        assert_eq!(block.word('Z'), None);
+        assert_eq!(block.checksum_valid(), None);
+        assert!(collector.take_block().is_none());
+    }
+
+    #[test]
+    fn block_collector_each_block() {
+        let mut parser = BlockParser::<i32>::default();
+        let mut collector = BlockCollector::<i32>::default();
+        parser.try_feed_str("G0 X1\n", &mut collector).unwrap();
+        assert_eq!(
+            collector.take_block().unwrap().word('X'),
+            Some(Decimal::new(1, 0))
+        );
+        parser
+            .try_feed_str("(only a comment)\n", &mut collector)
+            .unwrap();
+        assert!(collector.take_block().is_none());
+        parser.try_feed_str("G1 Y2", &mut collector).unwrap();
+        assert!(collector.take_block().is_none());
+        parser.try_end(&mut collector).unwrap();
+        let block = collector.take_block().unwrap();
+        assert_eq!(block.g_codes(), [Decimal::new(1, 0)]);
+        assert_eq!(block.word('X'), None);
+        assert_eq!(block.word('Y'), Some(Decimal::new(2, 0)));
+    }
+
+    #[test]
+    fn block_collector_pending() {
+        let mut parser = BlockParser::<i32>::default();
+        let mut collector = BlockCollector::<i32>::default();
+        let result = parser.try_feed_str("G0 X1\nG1 X2\n", &mut collector);
+        assert_eq!(
+            result.as_ref().map_err(Error::kind),
+            Err(&ErrorKind::BlockBuilderError(CollectorError::Pending))
+        );
+        let block = collector.take_block().unwrap();
+        assert_eq!(block.g_codes(), [Decimal::new(0, 0)]);
+        assert_eq!(block.word('X'), Some(Decimal::new(1, 0)));
+        assert!(collector.take_block().is_none());
+    }
+
+    #[test]
+    fn block_collector_debug() {
+        let mut parser = BlockParser::<i32>::default();
+        let mut collector = BlockCollector::<i32>::default();
+        parser.try_feed_str("G0 X4\n", &mut collector).unwrap();
+        let block = collector.take_block().unwrap();
+        let mut list = Buffer::<256>::default();
+        write!(list, "{:?}", block.words).unwrap();
+        let mut slice = Buffer::<256>::default();
+        write!(slice, "{:?}", block.words()).unwrap();
+        // The unused slots hold a filler word with the address `A`.
+        assert_eq!(list.as_str(), slice.as_str());
+        assert!(!list.as_str().contains("'A'"));
+    }
+
+    #[test]
+    fn block_collector_index() {
+        let mut parser = BlockParser::<i32>::default();
+        let mut collector = BlockCollector::<i32>::default();
+        parser.try_feed_str("P2=3\n", &mut collector).unwrap();
+        let block = collector.take_block().unwrap();
+        assert_eq!(block.words()[0].index(), Some(2));
+        assert_eq!(block.words()[0].number(), Decimal::new(3, 0));
+        assert_eq!(block.word('P'), None);
+    }
+
+    #[test]
+    fn block_collector_checksum() {
+        let config = Config::default().with_checksums(true);
+        let mut parser = BlockParser::<i32>::new(config);
+        let mut collector = BlockCollector::<i32>::default();
+        parser
+            .try_feed_str("N123 G1 X1*97\n", &mut collector)
+            .unwrap();
+        assert_eq!(collector.take_block().unwrap().checksum_valid(), Some(true));
+        parser
+            .try_feed_str("N123 G1 X1*98\n", &mut collector)
+            .unwrap();
+        assert_eq!(
+            collector.take_block().unwrap().checksum_valid(),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn block_collector_capacity() {
+        let config = Config::default().with_recovery(true);
+        let mut parser = BlockParser::<i32>::new(config);
+        let mut collector = BlockCollector::<i32, i32, i32, 2>::default();
+        let result = parser.try_feed_str("G1 X1 Y2 Z3\n", &mut collector);
+        assert_eq!(
+            result.as_ref().map_err(Error::kind),
+            Err(&ErrorKind::BlockBuilderError(CollectorError::Capacity))
+        );
+        assert!(collector.take_block().is_none());
+        parser.try_feed_str("G1 X1 Y2\n", &mut collector).unwrap();
+        assert_eq!(collector.take_block().unwrap().words().len(), 2);
+    }
+
+    #[test]
+    fn block_collector_unsupported() {
+        let config = Config::default().with_parameters(true);
+        let mut parser = BlockParser::<i32>::new(config);
+        let mut collector = BlockCollector::<i32>::default();
+        let result = parser.try_feed_str("X#1\n", &mut collector);
+        assert_eq!(
+            result.as_ref().map_err(Error::kind),
+            Err(&ErrorKind::BlockBuilderError(CollectorError::Unsupported))
+        );
+    }
+}
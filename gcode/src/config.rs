@@ -0,0 +1,262 @@
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// The options that select the dialect of G-code accepted by the [`BlockParser`].
+///
+/// The default configuration accepts only the ISO 6983-1 dialect described in the crate
+/// documentation. Each extension must be enabled explicitly.
+///
+///     use gcode::{BlockParser, Config};
+///
+///     let config = Config::new().with_semicolon_comments(true);
+///     let parser = BlockParser::<i32>::new(config);
+///
+/// [`BlockParser`]: crate::BlockParser
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+// Each flag enables an independent dialect feature.
+#[allow(clippy::struct_excessive_bools)]
+pub struct Config {
+    semicolon_comments: bool,
+    parameters: bool,
+    expressions: bool,
+    o_words: bool,
+    checksums: bool,
+    recovery: bool,
+    ascii_comments: bool,
+    unique_words: bool,
+}
+
+impl Config {
+    /// Creates a new [`Config`] with the default options.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            semicolon_comments: false,
+            parameters: false,
+            expressions: false,
+            o_words: false,
+            checksums: false,
+            recovery: false,
+            ascii_comments: false,
+            unique_words: false,
+        }
+    }
+
+    /// Returns `true` if comments may start with the `;` character.
+    #[must_use]
+    pub const fn semicolon_comments(&self) -> bool {
+        self.semicolon_comments
+    }
+
+    /// Enables or disables comments that start with the `;` character and end at the next line
+    /// feed character (`\n`.)
+    #[must_use]
+    pub const fn with_semicolon_comments(self, semicolon_comments: bool) -> Self {
+        Self {
+            semicolon_comments,
+            ..self
+        }
+    }
+
+    /// Returns `true` if numbered parameters are enabled.
+    #[must_use]
+    pub const fn parameters(&self) -> bool {
+        self.parameters
+    }
+
+    /// Enables or disables numbered parameters.
+    ///
+    /// A word may use the value of a parameter (e.g., `X#5`) and a block may assign a value to a
+    /// parameter (e.g., `#5=12.5`.)
+    #[must_use]
+    pub const fn with_parameters(self, parameters: bool) -> Self {
+        Self { parameters, ..self }
+    }
+
+    /// Returns `true` if expressions are enabled.
+    #[must_use]
+    pub const fn expressions(&self) -> bool {
+        self.expressions
+    }
+
+    /// Enables or disables bracketed expressions.
+    ///
+    /// A word may use the value of an expression (e.g., `X[#1 + 2.5 * COS[30]]`) and a block may
+    /// assign the value of an expression to a parameter (e.g., `#5=[#1 / 2]`.) Expressions may use
+    /// numbered parameters even if [`Config::with_parameters`] is disabled.
+    #[must_use]
+    pub const fn with_expressions(self, expressions: bool) -> Self {
+        Self {
+            expressions,
+            ..self
+        }
+    }
+
+    /// Returns `true` if O-words are enabled.
+    #[must_use]
+    pub const fn o_words(&self) -> bool {
+        self.o_words
+    }
+
+    /// Enables or disables O-words for subroutines and control flow (e.g., `O100 if [#1 GT 2]`.)
+    ///
+    /// When O-words are enabled, the `O` address is no longer available for data words. The
+    /// arguments of O-words are always parsed as expressions, even if
+    /// [`Config::with_expressions`] is disabled.
+    #[must_use]
+    pub const fn with_o_words(self, o_words: bool) -> Self {
+        Self { o_words, ..self }
+    }
+
+    /// Returns `true` if checksums are enabled.
+    #[must_use]
+    pub const fn checksums(&self) -> bool {
+        self.checksums
+    }
+
+    /// Enables or disables RepRap-style checksums at the end of a block (e.g., `N123 G1 X1*57`.)
+    ///
+    /// The checksum is the XOR of the bytes of the block that precede the `*` character.
+    #[must_use]
+    pub const fn with_checksums(self, checksums: bool) -> Self {
+        Self { checksums, ..self }
+    }
+
+    /// Returns `true` if error recovery is enabled.
+    #[must_use]
+    pub const fn recovery(&self) -> bool {
+        self.recovery
+    }
+
+    /// Enables or disables error recovery.
+    ///
+    /// When error recovery is enabled, an error aborts only the current block. The parser still
+    /// returns the error, then skips to the next line feed character (`\n`) and calls
+    /// [`BlockBuilder::abort_block`] instead of [`BlockBuilder::end_block`]. When error recovery
+    /// is disabled, the state of the parser is unspecified after an error.
+    ///
+    /// [`BlockBuilder::abort_block`]: crate::BlockBuilder::abort_block
+    /// [`BlockBuilder::end_block`]: crate::BlockBuilder::end_block
+    #[must_use]
+    pub const fn with_recovery(self, recovery: bool) -> Self {
+        Self { recovery, ..self }
+    }
+
+    /// Returns `true` if comments are restricted to ASCII.
+    #[must_use]
+    pub const fn ascii_comments(&self) -> bool {
+        self.ascii_comments
+    }
+
+    /// Restricts comments to ASCII characters.
+    ///
+    /// When enabled, a non-ASCII character (or byte, for [`BlockParser::try_feed_byte`]) in a
+    /// comment is an invalid character. When disabled, it is passed through to
+    /// [`BlockBuilder::comment_char`].
+    ///
+    /// [`BlockBuilder::comment_char`]: crate::BlockBuilder::comment_char
+    /// [`BlockParser::try_feed_byte`]: crate::BlockParser::try_feed_byte
+    #[must_use]
+    pub const fn with_ascii_comments(self, ascii_comments: bool) -> Self {
+        Self {
+            ascii_comments,
+            ..self
+        }
+    }
+
+    /// Returns `true` if duplicate words are rejected.
+    #[must_use]
+    pub const fn unique_words(&self) -> bool {
+        self.unique_words
+    }
+
+    /// Enables or disables the rejection of duplicate words.
+    ///
+    /// When enabled, a block may not contain two words with the same address (e.g., `X1 X2`), or
+    /// with the same address and index (e.g., `P2=1 P2=3`.) Addresses are case-insensitive. The
+    /// G and M addresses and parameter assignments are not checked. A duplicate word is reported
+    /// as [`ErrorKind::DuplicateWord`] and is not passed to the [`BlockBuilder`].
+    ///
+    /// Only indexes 0 to 31 can be checked. A word with a larger index is reported as
+    /// [`ErrorKind::UnsupportedIndex`].
+    ///
+    /// [`BlockBuilder`]: crate::BlockBuilder
+    /// [`ErrorKind::DuplicateWord`]: crate::ErrorKind::DuplicateWord
+    /// [`ErrorKind::UnsupportedIndex`]: crate::ErrorKind::UnsupportedIndex
+    #[must_use]
+    pub const fn with_unique_words(self, unique_words: bool) -> Self {
+        Self {
+            unique_words,
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        assert_eq!(Config::default(), Config::new());
+    }
+
+    #[test]
+    fn semicolon_comments() {
+        let config = Config::new().with_semicolon_comments(true);
+        assert!(config.semicolon_comments());
+        assert!(!config.parameters());
+    }
+
+    #[test]
+    fn parameters() {
+        let config = Config::new().with_parameters(true);
+        assert!(!config.semicolon_comments());
+        assert!(config.parameters());
+        assert!(!config.expressions());
+    }
+
+    #[test]
+    fn expressions() {
+        let config = Config::new().with_expressions(true);
+        assert!(!config.parameters());
+        assert!(config.expressions());
+        assert!(!config.o_words());
+    }
+
+    #[test]
+    fn o_words() {
+        let config = Config::new().with_o_words(true);
+        assert!(!config.expressions());
+        assert!(config.o_words());
+    }
+
+    #[test]
+    fn checksums() {
+        let config = Config::new().with_checksums(true);
+        assert!(!config.o_words());
+        assert!(config.checksums());
+    }
+
+    #[test]
+    fn recovery() {
+        let config = Config::new().with_recovery(true);
+        assert!(!config.checksums());
+        assert!(config.recovery());
+    }
+
+    #[test]
+    fn ascii_comments() {
+        let config = Config::new().with_ascii_comments(true);
+        assert!(!config.recovery());
+        assert!(config.ascii_comments());
+    }
+
+    #[test]
+    fn unique_words() {
+        let config = Config::new().with_unique_words(true);
+        assert!(!config.ascii_comments());
+        assert!(config.unique_words());
+    }
+}
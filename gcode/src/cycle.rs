@@ -0,0 +1,509 @@
+use crate::decimal::Decimal;
+use crate::interpreter::InterpreterError;
+use crate::machine::Point;
+use crate::significand::Significand;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// The canned drilling and boring cycles.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum CannedCycle {
+    /// High-speed peck drilling (`G73`), which backs off slightly after each peck to break the
+    /// chip.
+    ChipBreak,
+
+    /// Drilling (`G81`.)
+    Drill,
+
+    /// Drilling with a dwell at the bottom of the hole (`G82`.)
+    DrillDwell,
+
+    /// Peck drilling (`G83`), which retracts to the retract level after each peck to clear the
+    /// chip.
+    PeckDrill,
+
+    /// Boring, with a retract at the feed rate (`G85`.)
+    Bore,
+
+    /// Boring with a dwell at the bottom of the hole, and a retract at the feed rate (`G89`.)
+    BoreDwell,
+}
+
+impl CannedCycle {
+    /// Returns `true` if the cycle drills in pecks of the depth of the `Q` word.
+    #[must_use]
+    pub const fn pecks(self) -> bool {
+        matches!(self, Self::ChipBreak | Self::PeckDrill)
+    }
+
+    /// Returns `true` if the cycle dwells at the bottom of the hole for the duration of the `P`
+    /// word.
+    #[must_use]
+    pub const fn dwells(self) -> bool {
+        matches!(self, Self::DrillDwell | Self::BoreDwell)
+    }
+}
+
+/// The return modes of canned cycles (`G98` and `G99`.)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum ReturnMode {
+    /// Retract to the initial level (i.e., the position of the axis before the first of a series
+    /// of canned cycles), or to the retract level if it is higher (`G98`.)
+    #[default]
+    InitialLevel,
+
+    /// Retract to the retract level (the `R` word) (`G99`.)
+    RetractLevel,
+}
+
+/// The words that canned cycles remember from one block to the next, until the motion mode
+/// changes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct CycleWords<S>
+where
+    S: Significand,
+{
+    /// The initial level, in machine coordinates.
+    pub(crate) initial: Option<Decimal<S>>,
+    /// The `R` word.
+    pub(crate) retract: Option<Decimal<S>>,
+    /// The word of the drilling axis (e.g., `Z` in the XY plane.)
+    pub(crate) bottom: Option<Decimal<S>>,
+    /// The `Q` word.
+    pub(crate) peck: Option<Decimal<S>>,
+    /// The `P` word.
+    pub(crate) dwell: Option<Decimal<S>>,
+}
+
+/// One step of a canned cycle, in machine coordinates.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Step<S>
+where
+    S: Significand,
+{
+    /// Rapid move to the point.
+    Rapid(Point<S>),
+    /// Straight move to the point at the feed rate.
+    Linear(Point<S>, Decimal<S>),
+    /// Dwell for the duration in seconds.
+    Dwell(Decimal<S>),
+}
+
+/// One canned cycle, resolved to machine coordinates.
+pub struct Cycle<S>
+where
+    S: Significand,
+{
+    /// The kind of cycle (the motion mode.)
+    pub(crate) kind: CannedCycle,
+    /// The axes of the plane, and the drilling axis normal to it.
+    pub(crate) axes: (usize, usize, usize),
+    /// The position of the first hole in the plane.
+    pub(crate) hole: (Decimal<S>, Decimal<S>),
+    /// The distance in the plane from each hole to the next.
+    pub(crate) spacing: (Decimal<S>, Decimal<S>),
+    /// The number of holes.
+    pub(crate) repeat: u16,
+    /// The level at the end of each hole.
+    pub(crate) clear: Decimal<S>,
+    /// The level at the start of the feed into each hole.
+    pub(crate) retract: Decimal<S>,
+    /// The level of the bottom of each hole.
+    pub(crate) bottom: Decimal<S>,
+    /// The depth of each peck.
+    pub(crate) peck: Decimal<S>,
+    /// The distance that `G73` backs off, and that `G83` stops above the previous peck.
+    pub(crate) clearance: Decimal<S>,
+    /// The duration of the dwell at the bottom, in seconds.
+    pub(crate) dwell: Decimal<S>,
+    /// The feed rate of the feed into each hole, and of the retract of `G85` and `G89`. See
+    /// [`Machine::linear`] for its units.
+    ///
+    /// [`Machine::linear`]: crate::Machine::linear
+    pub(crate) feed_rate: Decimal<S>,
+}
+
+impl<S> Cycle<S>
+where
+    S: Significand,
+{
+    /// Drills every hole, starting from `start`, and passes each step to `sink`.
+    pub(crate) fn run<E, F>(&self, start: Point<S>, mut sink: F) -> Result<(), InterpreterError<E>>
+    where
+        F: FnMut(Step<S>) -> Result<(), InterpreterError<E>>,
+    {
+        let mut mover = Mover {
+            sink: &mut sink,
+            position: start,
+            normal: self.axes.2,
+        };
+        let (mut first, mut second) = self.hole;
+        for index in 0..self.repeat {
+            if index > 0 {
+                first = first
+                    .checked_add(self.spacing.0)
+                    .ok_or(InterpreterError::Overflow)?;
+                second = second
+                    .checked_add(self.spacing.1)
+                    .ok_or(InterpreterError::Overflow)?;
+            }
+            self.drill(&mut mover, first, second)?;
+        }
+        Ok(())
+    }
+
+    /// Drills one hole at `(first, second)` in the plane.
+    fn drill<E>(
+        &self,
+        mover: &mut Mover<'_, S, E>,
+        first: Decimal<S>,
+        second: Decimal<S>,
+    ) -> Result<(), InterpreterError<E>> {
+        let (first_axis, second_axis, _) = self.axes;
+        if mover.level() < self.retract {
+            mover.rapid(self.retract)?;
+        }
+        let mut above = mover.position;
+        above.set_axis(first_axis, first);
+        above.set_axis(second_axis, second);
+        mover.rapid_to(above)?;
+        mover.rapid(self.retract)?;
+        if self.kind.pecks() {
+            self.peck(mover)?;
+        } else {
+            mover.linear(self.bottom, self.feed_rate)?;
+        }
+        if self.kind.dwells() {
+            (mover.sink)(Step::Dwell(self.dwell))?;
+        }
+        if matches!(self.kind, CannedCycle::Bore | CannedCycle::BoreDwell) {
+            mover.linear(self.retract, self.feed_rate)?;
+        }
+        mover.rapid(self.clear)
+    }
+
+    fn peck<E>(&self, mover: &mut Mover<'_, S, E>) -> Result<(), InterpreterError<E>> {
+        let overflow = || InterpreterError::Overflow;
+        let mut depth = self.retract;
+        loop {
+            if depth < self.retract {
+                // Return to just above the previous peck.
+                let start = depth.checked_add(self.clearance).ok_or_else(overflow)?;
+                mover.rapid(start.min(self.retract))?;
+            }
+            depth = depth
+                .checked_sub(self.peck)
+                .ok_or_else(overflow)?
+                .max(self.bottom);
+            mover.linear(depth, self.feed_rate)?;
+            if depth == self.bottom {
+                return Ok(());
+            }
+            if CannedCycle::PeckDrill == self.kind {
+                mover.rapid(self.retract)?;
+            } else {
+                let back = depth.checked_add(self.clearance).ok_or_else(overflow)?;
+                mover.rapid(back.min(self.retract))?;
+            }
+        }
+    }
+}
+
+/// Passes the moves of a cycle to a sink and tracks the position.
+struct Mover<'a, S, E>
+where
+    S: Significand,
+{
+    sink: &'a mut dyn FnMut(Step<S>) -> Result<(), InterpreterError<E>>,
+    position: Point<S>,
+    normal: usize,
+}
+
+impl<S, E> Mover<'_, S, E>
+where
+    S: Significand,
+{
+    const fn level(&self) -> Decimal<S> {
+        self.position.axis(self.normal)
+    }
+
+    const fn along(&self, level: Decimal<S>) -> Point<S> {
+        let mut end = self.position;
+        end.set_axis(self.normal, level);
+        end
+    }
+
+    /// Rapid move along the drilling axis. Does nothing if the position is already at `level`.
+    fn rapid(&mut self, level: Decimal<S>) -> Result<(), InterpreterError<E>> {
+        self.rapid_to(self.along(level))
+    }
+
+    fn rapid_to(&mut self, end: Point<S>) -> Result<(), InterpreterError<E>> {
+        if end != self.position {
+            (self.sink)(Step::Rapid(end))?;
+            self.position = end;
+        }
+        Ok(())
+    }
+
+    fn linear(
+        &mut self,
+        level: Decimal<S>,
+        feed_rate: Decimal<S>,
+    ) -> Result<(), InterpreterError<E>> {
+        let end = self.along(level);
+        (self.sink)(Step::Linear(end, feed_rate))?;
+        self.position = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::block::{BlockParser, Error, ErrorKind};
+    use crate::config::Config;
+    use crate::interpreter::{Interpreter, MotionMode};
+    use crate::testing::{error, interpret, moves, point, Move};
+
+    extern crate std;
+    use std::vec::Vec;
+
+    use Move::{Dwell, Rapid};
+
+    fn linear(end: Point<i32>) -> Move {
+        Move::Linear(end, Decimal::new(100, 0))
+    }
+
+    fn z(x: i32, z: i32, negative_exponent: u32) -> Point<i32> {
+        Point::new(
+            Decimal::new(x, 0),
+            Decimal::new(0, 0),
+            Decimal::new(z, negative_exponent),
+        )
+    }
+
+    /// Returns the moves after the first block.
+    fn drilled(s: &str) -> Vec<Move> {
+        moves(s).split_off(1)
+    }
+
+    #[test]
+    fn drill() {
+        assert_eq!(
+            drilled("G0 Z10\nG99 G81 X1 Y2 Z-3 R2 F100\n"),
+            [
+                Rapid(point(1, 2, 10)),
+                Rapid(point(1, 2, 2)),
+                linear(point(1, 2, -3)),
+                Rapid(point(1, 2, 2)),
+            ]
+        );
+        assert_eq!(
+            drilled("G0 Z10\nG98 G81 X1 Y2 Z-3 R2 F100\n")[3..],
+            [Rapid(point(1, 2, 10))]
+        );
+    }
+
+    #[test]
+    fn drill_below_retract_level() {
+        assert_eq!(
+            drilled("G0 Z-1\nG98 G81 X1 Z-3 R2 F100\n"),
+            [
+                Rapid(point(0, 0, 2)),
+                Rapid(point(1, 0, 2)),
+                linear(point(1, 0, -3)),
+                Rapid(point(1, 0, 2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn drill_sticky_words() {
+        let interpreter = interpret(Vec::new(), "G0 Z10\nG98 G81 X1 Z-3 R2 F100\nX2\n");
+        let state = interpreter.state();
+        assert_eq!(
+            state.motion(),
+            Some(MotionMode::CannedCycle(CannedCycle::Drill))
+        );
+        assert_eq!(state.return_mode(), ReturnMode::InitialLevel);
+        assert_eq!(
+            interpreter.into_machine()[8..],
+            [
+                Rapid(point(2, 0, 10)),
+                Rapid(point(2, 0, 2)),
+                linear(point(2, 0, -3)),
+                Rapid(point(2, 0, 10)),
+            ]
+        );
+        assert_eq!(
+            error(Vec::new(), "G81 X1 Z-1 R1 F1\nG80\nG81 X2\n"),
+            InterpreterError::MissingWord('R')
+        );
+    }
+
+    #[test]
+    fn drill_invalid_block() {
+        let config = Config::new().with_recovery(true);
+        let mut parser = BlockParser::<i32>::new(config);
+        let mut interpreter = Interpreter::new(Vec::new());
+        parser.try_feed_str("G0 Z10\n", &mut interpreter).unwrap();
+        // The block is missing the `R` word, so its `Z` word is not remembered.
+        assert!(parser
+            .try_feed_str("G98 G81 X1 Z-3\n", &mut interpreter)
+            .is_err());
+        let result = parser.try_feed_str("G81 X2 R2 F100\n", &mut interpreter);
+        assert_eq!(
+            result.as_ref().map_err(Error::kind),
+            Err(&ErrorKind::BlockBuilderError(
+                InterpreterError::MissingWord('Z')
+            ))
+        );
+        parser
+            .try_feed_str("G0 Z5\nG81 X2 Z-3 R2 F100\n", &mut interpreter)
+            .unwrap();
+        assert_eq!(interpreter.state().position(), point(2, 0, 5));
+    }
+
+    #[test]
+    fn drill_dwell() {
+        assert_eq!(
+            drilled("G0 Z10\nG99 G82 X1 Z-3 R2 P0.5 F100\n")[2..],
+            [
+                linear(point(1, 0, -3)),
+                Dwell(Decimal::new(5, 1)),
+                Rapid(point(1, 0, 2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn peck_drill() {
+        assert_eq!(
+            drilled("G0 Z10\nG99 G83 X0 Z-5 R1 Q2 F100\n"),
+            [
+                Rapid(point(0, 0, 1)),
+                linear(point(0, 0, -1)),
+                Rapid(point(0, 0, 1)),
+                Rapid(z(0, -746, 3)),
+                linear(point(0, 0, -3)),
+                Rapid(point(0, 0, 1)),
+                Rapid(z(0, -2746, 3)),
+                linear(point(0, 0, -5)),
+                Rapid(point(0, 0, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn chip_break() {
+        assert_eq!(
+            drilled("G0 Z10\nG99 G73 X0 Z-5 R1 Q2 F100\n"),
+            [
+                Rapid(point(0, 0, 1)),
+                linear(point(0, 0, -1)),
+                Rapid(z(0, -746, 3)),
+                linear(point(0, 0, -3)),
+                Rapid(z(0, -2746, 3)),
+                linear(point(0, 0, -5)),
+                Rapid(point(0, 0, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn bore() {
+        assert_eq!(
+            drilled("G0 Z10\nG98 G85 X0 Z-5 R1 F100\n")[1..],
+            [
+                linear(point(0, 0, -5)),
+                linear(point(0, 0, 1)),
+                Rapid(point(0, 0, 10)),
+            ]
+        );
+        assert_eq!(
+            drilled("G0 Z10\nG99 G89 X0 Z-5 R1 P2 F100\n")[1..],
+            [
+                linear(point(0, 0, -5)),
+                Dwell(Decimal::new(2, 0)),
+                linear(point(0, 0, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn incremental_repeat() {
+        assert_eq!(
+            drilled("G0 Z10\nG91 G99 G81 X5 Z-4 R-8 L3 F100\n"),
+            [
+                Rapid(point(5, 0, 10)),
+                Rapid(point(5, 0, 2)),
+                linear(point(5, 0, -2)),
+                Rapid(point(5, 0, 2)),
+                Rapid(point(10, 0, 2)),
+                linear(point(10, 0, -2)),
+                Rapid(point(10, 0, 2)),
+                Rapid(point(15, 0, 2)),
+                linear(point(15, 0, -2)),
+                Rapid(point(15, 0, 2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn cycle_errors() {
+        assert_eq!(
+            error(Vec::new(), "G81 X1 Z-1\n"),
+            InterpreterError::MissingWord('R')
+        );
+        assert_eq!(
+            error(Vec::new(), "G81 X1 R1\n"),
+            InterpreterError::MissingWord('Z')
+        );
+        assert_eq!(
+            error(Vec::new(), "G83 X1 Z-1 R1\n"),
+            InterpreterError::MissingWord('Q')
+        );
+        assert_eq!(
+            error(Vec::new(), "G82 X1 Z-1 R1\n"),
+            InterpreterError::MissingWord('P')
+        );
+        assert_eq!(
+            error(Vec::new(), "G81 X1 Z1 R-1\n"),
+            InterpreterError::InvalidWord('R')
+        );
+        assert_eq!(
+            error(Vec::new(), "G83 X1 Z-1 R1 Q0\n"),
+            InterpreterError::InvalidWord('Q')
+        );
+        assert_eq!(
+            error(Vec::new(), "G82 X1 Z-1 R1 P-1\n"),
+            InterpreterError::InvalidWord('P')
+        );
+        assert_eq!(
+            error(Vec::new(), "G81 X1 Z-1 R1 L0\n"),
+            InterpreterError::InvalidWord('L')
+        );
+        assert_eq!(
+            error(Vec::new(), "G81 X1 Z-1 R1 L1.5\n"),
+            InterpreterError::InvalidWord('L')
+        );
+        assert_eq!(
+            error(Vec::new(), "G91 G81 X1000000000 Z-1 R1 L3\n"),
+            InterpreterError::Overflow
+        );
+        assert_eq!(
+            error(Vec::new(), "G93 G81 X1 Z-1 R1\n"),
+            InterpreterError::Unsupported
+        );
+        assert_eq!(
+            error(Vec::new(), "G84 X1 Z-1 R1\n"),
+            InterpreterError::Unsupported
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use core::cmp::Ordering;
 use core::fmt::Debug;
 
 use crate::sign::Sign;
@@ -24,7 +25,10 @@ use defmt::Format;
 /// distance of `25μm` would be stored as a significand of `25` and a negative exponent of `6`.
 ///
 /// `25μm = 0.000025m = 25×10`<sup>`-6`</sup>`m`.
-#[derive(Debug, PartialEq)]
+///
+/// [`Decimal`] numbers compare numerically. For example, `1.50` (`150×10`<sup>`-2`</sup>) and
+/// `1.5` (`15×10`<sup>`-1`</sup>) are equal.
+#[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "defmt", derive(Format))]
 pub struct Decimal<S>
 where
@@ -55,9 +59,231 @@ where
     pub const fn negative_exponent(&self) -> u32 {
         self.negative_exponent
     }
+
+    /// Converts the [`Decimal`] number to an integer count of `10`<sup>`-negative_exponent`</sup>
+    /// units, rounding if necessary. Returns `None` if overflow occurred.
+    ///
+    /// For example, if the base unit is millimeters, `to_scaled(6, rounding)` converts to
+    /// nanometers.
+    #[must_use]
+    pub fn to_scaled(self, negative_exponent: u32, rounding: Rounding) -> Option<Rounded<S>> {
+        if negative_exponent >= self.negative_exponent {
+            let value = self.scale_up(negative_exponent)?;
+            Some(Rounded::new(value, true))
+        } else {
+            let exp = self.negative_exponent - negative_exponent;
+            let (quotient, digit, sticky) = shr10(self.significand, exp);
+            let away = match rounding {
+                Rounding::Truncate => false,
+                Rounding::HalfEven => {
+                    digit > 5 || (digit == 5 && (sticky || quotient.div_rem10().1 % 2 == 1))
+                }
+                Rounding::HalfAwayFromZero => digit >= 5,
+            };
+            let value = if !away {
+                quotient
+            } else if self.significand.is_negative() {
+                quotient.checked_sub_unsigned(1)?
+            } else {
+                quotient.checked_add_unsigned(1)?
+            };
+            Some(Rounded::new(value, digit == 0 && !sticky))
+        }
+    }
+
+    /// Converts the [`Decimal`] number to an equivalent [`Decimal`] number with the specified
+    /// negative exponent, rounding if necessary. Returns `None` if overflow occurred.
+    #[must_use]
+    pub fn rescale(self, negative_exponent: u32, rounding: Rounding) -> Option<Rounded<Self>> {
+        let scaled = self.to_scaled(negative_exponent, rounding)?;
+        let exact = scaled.is_exact();
+        Some(Rounded::new(
+            Self::new(scaled.value(), negative_exponent),
+            exact,
+        ))
+    }
+
+    /// Converts two [`Decimal`] numbers to equivalent [`Decimal`] numbers with the same negative
+    /// exponent, the larger of the two. Returns `None` if overflow occurred.
+    #[must_use]
+    pub fn align(self, rhs: Self) -> Option<(Self, Self)> {
+        let negative_exponent = self.negative_exponent.max(rhs.negative_exponent);
+        Some((
+            Self::new(self.scale_up(negative_exponent)?, negative_exponent),
+            Self::new(rhs.scale_up(negative_exponent)?, negative_exponent),
+        ))
+    }
+
+    /// Checked addition. Computes `self + rhs`, returning `None` if overflow occurred.
+    #[must_use]
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let (lhs, rhs) = self.align(rhs)?;
+        let significand = lhs.significand.checked_add(rhs.significand)?;
+        Some(Self::new(significand, lhs.negative_exponent))
+    }
+
+    /// Checked subtraction. Computes `self - rhs`, returning `None` if overflow occurred.
+    #[must_use]
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        let (lhs, rhs) = self.align(rhs)?;
+        let significand = lhs.significand.checked_sub(rhs.significand)?;
+        Some(Self::new(significand, lhs.negative_exponent))
+    }
+
+    /// Checked multiplication. Computes `self × rhs`, returning `None` if overflow occurred.
+    #[must_use]
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let significand = self.significand.checked_mul(rhs.significand)?;
+        let negative_exponent = self.negative_exponent.checked_add(rhs.negative_exponent)?;
+        Some(Self::new(significand, negative_exponent))
+    }
+
+    /// Checked negation. Computes `-self`, returning `None` if overflow occurred.
+    #[must_use]
+    pub fn checked_neg(self) -> Option<Self> {
+        let significand = self.significand.checked_neg()?;
+        Some(Self::new(significand, self.negative_exponent))
+    }
+
+    /// Checked absolute value. Computes `|self|`, returning `None` if overflow occurred.
+    #[must_use]
+    pub fn checked_abs(self) -> Option<Self> {
+        if self.significand.is_negative() {
+            self.checked_neg()
+        } else {
+            Some(self)
+        }
+    }
+
+    /// Returns the significand of the equivalent [`Decimal`] number with a negative exponent that
+    /// is at least as large as this one's. Returns `None` if overflow occurred.
+    fn scale_up(self, negative_exponent: u32) -> Option<S> {
+        if self.significand.is_zero() {
+            // Zero never overflows, no matter how large the power of ten.
+            Some(self.significand)
+        } else {
+            let exp = negative_exponent - self.negative_exponent;
+            self.significand.checked_shl10(exp)
+        }
+    }
+}
+
+impl<S> PartialEq for Decimal<S>
+where
+    S: Significand,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S> Eq for Decimal<S> where S: Significand {}
+
+impl<S> PartialOrd for Decimal<S>
+where
+    S: Significand,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> Ord for Decimal<S>
+where
+    S: Significand,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        let negative_exponent = self.negative_exponent.max(other.negative_exponent);
+        match (
+            self.scale_up(negative_exponent),
+            other.scale_up(negative_exponent),
+        ) {
+            (Some(lhs), Some(rhs)) => lhs.cmp(&rhs),
+            // Only the number with the smaller negative exponent is scaled. If it overflows, its
+            // magnitude is larger than the other number's.
+            (None, _) => {
+                if self.significand.is_negative() {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }
+            }
+            (_, None) => {
+                if other.significand.is_negative() {
+                    Ordering::Greater
+                } else {
+                    Ordering::Less
+                }
+            }
+        }
+    }
+}
+
+/// Computes `significand × 10`<sup>`-exp`</sup>, rounding toward zero. Returns the quotient, the
+/// most significant discarded digit, and whether any other discarded digit is non-zero.
+fn shr10<S>(significand: S, exp: u32) -> (S, u8, bool)
+where
+    S: Significand,
+{
+    let mut quotient = significand;
+    let mut digit = 0;
+    let mut sticky = false;
+    let mut exp = exp;
+    while exp > 0 {
+        sticky |= digit != 0;
+        (quotient, digit) = quotient.div_rem10();
+        exp -= 1;
+        if quotient.is_zero() && exp > 0 {
+            // The remaining discarded digits are all zero.
+            sticky |= digit != 0;
+            digit = 0;
+            break;
+        }
+    }
+    (quotient, digit, sticky)
 }
 
-impl<S> Eq for Decimal<S> where S: Eq + Significand {}
+/// The rounding mode of [`Decimal::to_scaled`] and [`Decimal::rescale`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum Rounding {
+    /// Round toward zero, discarding the excess digits.
+    Truncate,
+
+    /// Round to the nearest value. Ties are rounded to the value with an even last digit (i.e.,
+    /// "banker's rounding".)
+    HalfEven,
+
+    /// Round to the nearest value. Ties are rounded away from zero.
+    HalfAwayFromZero,
+}
+
+/// The result of a conversion that may round.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Rounded<T> {
+    value: T,
+    exact: bool,
+}
+
+impl<T> Rounded<T> {
+    const fn new(value: T, exact: bool) -> Self {
+        Self { value, exact }
+    }
+
+    /// Returns the converted value.
+    pub fn value(self) -> T {
+        self.value
+    }
+
+    /// Returns `true` if the conversion did not lose precision (i.e., no non-zero digits were
+    /// discarded.)
+    pub const fn is_exact(&self) -> bool {
+        self.exact
+    }
+}
+
+impl<T> Eq for Rounded<T> where T: Eq {}
 
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(Format))]
@@ -159,6 +385,10 @@ where
         }
     }
 
+    pub const fn is_empty(&self) -> bool {
+        matches!(self.state, State::Start)
+    }
+
     pub const fn try_end(&self) -> Result<Decimal<S>, Error> {
         if matches!(self.state, State::Integer | State::Fraction) {
             let number = Decimal {
@@ -229,6 +459,259 @@ mod tests {
         assert_eq!(negative_exponent, 4);
     }
 
+    #[test]
+    fn to_scaled_exact() {
+        let decimal = Decimal::new(-12_345_i64, 3);
+        assert_eq!(
+            decimal.to_scaled(6, Rounding::Truncate),
+            Some(Rounded::new(-12_345_000, true))
+        );
+        assert_eq!(
+            decimal.to_scaled(3, Rounding::HalfEven),
+            Some(Rounded::new(-12_345, true))
+        );
+        assert_eq!(
+            Decimal::new(1_200, 3).to_scaled(1, Rounding::HalfEven),
+            Some(Rounded::new(12, true))
+        );
+    }
+
+    #[test]
+    fn to_scaled_zero() {
+        assert_eq!(
+            Decimal::new(0_i8, 0).to_scaled(200, Rounding::Truncate),
+            Some(Rounded::new(0, true))
+        );
+    }
+
+    #[test]
+    fn to_scaled_overflow() {
+        assert_eq!(
+            Decimal::new(3_i32, 0).to_scaled(9, Rounding::Truncate),
+            None
+        );
+        assert_eq!(
+            Decimal::new(u8::MAX, 0).to_scaled(1, Rounding::Truncate),
+            None
+        );
+    }
+
+    #[test]
+    fn to_scaled_limits() {
+        assert_eq!(
+            Decimal::new(i16::MIN, 1).to_scaled(0, Rounding::HalfAwayFromZero),
+            Some(Rounded::new(-3_277, false))
+        );
+        assert_eq!(
+            Decimal::new(i8::MAX, 1).to_scaled(0, Rounding::HalfEven),
+            Some(Rounded::new(13, false))
+        );
+        assert_eq!(
+            Decimal::new(u64::MAX, 1).to_scaled(0, Rounding::HalfAwayFromZero),
+            Some(Rounded::new(1_844_674_407_370_955_162, false))
+        );
+    }
+
+    #[test]
+    fn to_scaled_truncate() {
+        assert_eq!(
+            Decimal::new(129_i32, 2).to_scaled(1, Rounding::Truncate),
+            Some(Rounded::new(12, false))
+        );
+        assert_eq!(
+            Decimal::new(-129_i32, 2).to_scaled(1, Rounding::Truncate),
+            Some(Rounded::new(-12, false))
+        );
+    }
+
+    #[test]
+    fn to_scaled_half_away_from_zero() {
+        let cases = [
+            (124, 12, false),
+            (125, 13, false),
+            (135, 14, false),
+            (-125, -13, false),
+            (-124, -12, false),
+            (120, 12, true),
+        ];
+        for (significand, expected, exact) in cases {
+            assert_eq!(
+                Decimal::new(significand, 2).to_scaled(1, Rounding::HalfAwayFromZero),
+                Some(Rounded::new(expected, exact)),
+                "{significand}"
+            );
+        }
+    }
+
+    #[test]
+    fn to_scaled_half_even() {
+        let cases = [
+            (124_i32, 2, 12),
+            (125, 2, 12),
+            (135, 2, 14),
+            (126, 2, 13),
+            (-125, 2, -12),
+            (-135, 2, -14),
+            (1_251, 3, 13),
+            (1_250, 3, 12),
+        ];
+        for (significand, negative_exponent, expected) in cases {
+            assert_eq!(
+                Decimal::new(significand, negative_exponent).to_scaled(1, Rounding::HalfEven),
+                Some(Rounded::new(expected, false)),
+                "{significand}"
+            );
+        }
+    }
+
+    #[test]
+    fn to_scaled_small() {
+        // 0.000049 rounds to 0.0000 and 0.00005 rounds to 0.0001.
+        assert_eq!(
+            Decimal::new(49_i32, 6).to_scaled(4, Rounding::HalfAwayFromZero),
+            Some(Rounded::new(0, false))
+        );
+        assert_eq!(
+            Decimal::new(5_i32, 5).to_scaled(4, Rounding::HalfAwayFromZero),
+            Some(Rounded::new(1, false))
+        );
+        assert_eq!(
+            Decimal::new(5_i32, 5).to_scaled(4, Rounding::HalfEven),
+            Some(Rounded::new(0, false))
+        );
+        assert_eq!(
+            Decimal::new(5_i32, 100).to_scaled(0, Rounding::HalfAwayFromZero),
+            Some(Rounded::new(0, false))
+        );
+        assert_eq!(
+            Decimal::new(0_i32, 100).to_scaled(0, Rounding::HalfEven),
+            Some(Rounded::new(0, true))
+        );
+    }
+
+    #[test]
+    fn rescale() {
+        let decimal = Decimal::new(-2_675_i32, 3);
+        assert_eq!(
+            decimal.rescale(2, Rounding::HalfEven),
+            Some(Rounded::new(Decimal::new(-268, 2), false))
+        );
+        let rescaled = Decimal::new(25_u16, 1).rescale(3, Rounding::Truncate);
+        assert_eq!(rescaled, Some(Rounded::new(Decimal::new(2_500, 3), true)));
+        assert_eq!(
+            rescaled.map(|rounded| rounded.value().negative_exponent()),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn eq() {
+        assert_eq!(Decimal::new(150_i32, 2), Decimal::new(15, 1));
+        assert_eq!(Decimal::new(0_i32, 3), Decimal::new(0, 0));
+        assert_ne!(Decimal::new(15_i32, 2), Decimal::new(15, 1));
+    }
+
+    #[test]
+    fn cmp() {
+        assert!(Decimal::new(-15_i32, 1) < Decimal::new(-149, 2));
+        assert!(Decimal::new(2_i32, 0) > Decimal::new(199, 2));
+        assert!(Decimal::new(0_u8, 0) < Decimal::new(1, 200));
+    }
+
+    #[test]
+    fn cmp_overflow() {
+        // 1000 × 10^-0 cannot be scaled to 10^-9 in an i32.
+        assert!(Decimal::new(1_000_i32, 0) > Decimal::new(1, 9));
+        assert!(Decimal::new(-1_000_i32, 0) < Decimal::new(-1, 9));
+        assert!(Decimal::new(1, 9) < Decimal::new(1_000_i32, 0));
+        assert!(Decimal::new(-1, 9) > Decimal::new(-1_000_i32, 0));
+    }
+
+    #[test]
+    fn align() {
+        let (lhs, rhs) = Decimal::new(15_i32, 1).align(Decimal::new(-3, 3)).unwrap();
+        assert_eq!(lhs.significand(), 1_500);
+        assert_eq!(lhs.negative_exponent(), 3);
+        assert_eq!(rhs.significand(), -3);
+        assert_eq!(rhs.negative_exponent(), 3);
+    }
+
+    #[test]
+    fn align_overflow() {
+        assert_eq!(Decimal::new(1_000_i32, 0).align(Decimal::new(1, 9)), None);
+    }
+
+    #[test]
+    fn checked_add() {
+        let sum = Decimal::new(15_i32, 1).checked_add(Decimal::new(-3, 3));
+        assert_eq!(sum, Some(Decimal::new(1_497, 3)));
+        assert_eq!(
+            Decimal::new(i8::MAX, 1).checked_add(Decimal::new(1, 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn checked_sub() {
+        let difference = Decimal::new(15_i32, 1).checked_sub(Decimal::new(25, 1));
+        assert_eq!(difference, Some(Decimal::new(-1, 0)));
+        assert_eq!(Decimal::new(0_u32, 0).checked_sub(Decimal::new(1, 1)), None);
+    }
+
+    #[test]
+    fn checked_mul() {
+        let product = Decimal::new(15_i32, 1).checked_mul(Decimal::new(-25, 2));
+        assert_eq!(product, Some(Decimal::new(-375, 3)));
+        assert_eq!(
+            Decimal::new(i16::MAX, 0).checked_mul(Decimal::new(2, 0)),
+            None
+        );
+        assert_eq!(
+            Decimal::new(1_i32, u32::MAX).checked_mul(Decimal::new(1, 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn checked_neg() {
+        assert_eq!(
+            Decimal::new(15_i32, 1).checked_neg(),
+            Some(Decimal::new(-15, 1))
+        );
+        assert_eq!(Decimal::new(i32::MIN, 1).checked_neg(), None);
+        assert_eq!(Decimal::new(1_u32, 1).checked_neg(), None);
+    }
+
+    #[test]
+    fn checked_abs() {
+        assert_eq!(
+            Decimal::new(-15_i32, 1).checked_abs(),
+            Some(Decimal::new(15, 1))
+        );
+        assert_eq!(
+            Decimal::new(15_u32, 1).checked_abs(),
+            Some(Decimal::new(15, 1))
+        );
+        assert_eq!(Decimal::new(i32::MIN, 1).checked_abs(), None);
+    }
+
+    #[test]
+    fn normalized() {
+        // Equality is numeric, so check the representation explicitly.
+        let cases = [
+            ("0.0", 0, 0),
+            ("-.0", 0, 0),
+            ("1.500", 15, 1),
+            ("+08.50", 85, 1),
+            ("10", 10, 0),
+        ];
+        for (s, significand, negative_exponent) in cases {
+            let decimal = DecimalParser::<i32>::default().try_feed_str_end(s).unwrap();
+            assert_eq!(decimal.significand(), significand, "{s}");
+            assert_eq!(decimal.negative_exponent(), negative_exponent, "{s}");
+        }
+    }
+
     #[test]
     fn capacity_integer() {
         let parser: DecimalParser<i32> = DecimalParser::default();
@@ -236,6 +719,98 @@ mod tests {
         assert_eq!(result, Err(Error::Capacity));
     }
 
+    #[test]
+    fn capacity_integer_i8() {
+        assert_eq!(
+            DecimalParser::<i8>::default().try_feed_str_end("127"),
+            Ok(Decimal::new(127, 0))
+        );
+        assert_eq!(
+            DecimalParser::<i8>::default().try_feed_str_end("-128"),
+            Ok(Decimal::new(-128, 0))
+        );
+        assert_eq!(
+            DecimalParser::<i8>::default().try_feed_str_end("128"),
+            Err(Error::Capacity)
+        );
+        assert_eq!(
+            DecimalParser::<i8>::default().try_feed_str_end("-129"),
+            Err(Error::Capacity)
+        );
+    }
+
+    #[test]
+    fn capacity_fraction_i8() {
+        assert_eq!(
+            DecimalParser::<i8>::default().try_feed_str_end("1.27"),
+            Ok(Decimal::new(127, 2))
+        );
+        assert_eq!(
+            DecimalParser::<i8>::default().try_feed_str_end("1.280"),
+            Err(Error::Capacity)
+        );
+    }
+
+    #[test]
+    fn capacity_integer_i16() {
+        assert_eq!(
+            DecimalParser::<i16>::default().try_feed_str_end("32767"),
+            Ok(Decimal::new(32767, 0))
+        );
+        assert_eq!(
+            DecimalParser::<i16>::default().try_feed_str_end("-32768"),
+            Ok(Decimal::new(-32768, 0))
+        );
+        assert_eq!(
+            DecimalParser::<i16>::default().try_feed_str_end("32768"),
+            Err(Error::Capacity)
+        );
+        assert_eq!(
+            DecimalParser::<i16>::default().try_feed_str_end("-32769"),
+            Err(Error::Capacity)
+        );
+    }
+
+    #[test]
+    fn capacity_fraction_i16() {
+        assert_eq!(
+            DecimalParser::<i16>::default().try_feed_str_end("-3.2768"),
+            Ok(Decimal::new(-32768, 4))
+        );
+        assert_eq!(
+            DecimalParser::<i16>::default().try_feed_str_end("3.27680"),
+            Err(Error::Capacity)
+        );
+    }
+
+    #[test]
+    fn capacity_integer_u32() {
+        let parser: DecimalParser<u32> = DecimalParser::default();
+        let result = parser.try_feed_str_end("4294967295");
+        assert_eq!(result, Ok(Decimal::new(u32::MAX, 0)));
+    }
+
+    #[test]
+    fn capacity_negative_u32() {
+        let parser: DecimalParser<u32> = DecimalParser::default();
+        let result = parser.try_feed_str_end("-1");
+        assert_eq!(result, Err(Error::Capacity));
+    }
+
+    #[test]
+    fn capacity_integer_i64() {
+        let parser: DecimalParser<i64> = DecimalParser::default();
+        let result = parser.try_feed_str_end("9223372036854775808");
+        assert_eq!(result, Err(Error::Capacity));
+    }
+
+    #[test]
+    fn integer_i64() {
+        let parser: DecimalParser<i64> = DecimalParser::default();
+        let result = parser.try_feed_str_end("-2147483648.000000001");
+        assert_eq!(result, Ok(Decimal::new(-2_147_483_648_000_000_001, 9)));
+    }
+
     #[test]
     fn capacity_fraction() {
         let parser: DecimalParser<i32> = DecimalParser::default();
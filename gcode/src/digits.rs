@@ -0,0 +1,50 @@
+use core::str;
+
+use crate::significand::Significand;
+
+/// The decimal digits of the magnitude of a significand.
+pub struct Digits {
+    // The longest significand (u64::MAX) has 20 digits.
+    digits: [u8; 20],
+    start: usize,
+}
+
+impl Digits {
+    pub fn new<S>(significand: S) -> Self
+    where
+        S: Significand,
+    {
+        let mut digits = [0; 20];
+        let mut start = digits.len();
+        let mut significand = significand;
+        loop {
+            let (quotient, digit) = significand.div_rem10();
+            start -= 1;
+            digits[start] = b'0' + digit;
+            significand = quotient;
+            if significand.is_zero() {
+                break;
+            }
+        }
+        Self { digits, start }
+    }
+
+    /// Returns the digits, most significant first. Zero is a single `0` digit.
+    pub fn as_str(&self) -> &str {
+        // The digits are always ASCII.
+        str::from_utf8(&self.digits[self.start..]).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn as_str() {
+        assert_eq!(Digits::new(0_i32).as_str(), "0");
+        assert_eq!(Digits::new(-1_024_i32).as_str(), "1024");
+        assert_eq!(Digits::new(i8::MIN).as_str(), "128");
+        assert_eq!(Digits::new(u64::MAX).as_str(), "18446744073709551615");
+    }
+}
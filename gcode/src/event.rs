@@ -0,0 +1,612 @@
+use core::convert::Infallible;
+
+use crate::block::{BlockBuilder, BlockParser, CommentKind, Error, ErrorKind};
+use crate::decimal::Decimal;
+use crate::expression::ExpressionToken;
+use crate::o_word::OWordKeyword;
+#[cfg(feature = "position")]
+use crate::position::Position;
+use crate::significand::Significand;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// An event of the [`BlockParser`].
+///
+/// Each variant corresponds to a method of the [`BlockBuilder`] trait, with the same arguments.
+/// See [`BlockParser::events`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum Event<S, I = S, N = I>
+where
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    /// See [`BlockBuilder::program_start`].
+    ProgramStart,
+
+    /// See [`BlockBuilder::sequence_number`].
+    SequenceNumber {
+        /// `true` if the sequence number starts with the alignment character `:`.
+        alignment: bool,
+        /// The sequence number.
+        number: Decimal<N>,
+    },
+
+    /// See [`BlockBuilder::g_code`].
+    GCode {
+        /// The number of the G code.
+        number: Decimal<S>,
+    },
+
+    /// See [`BlockBuilder::m_code`].
+    MCode {
+        /// The number of the M code.
+        number: Decimal<S>,
+    },
+
+    /// See [`BlockBuilder::data`].
+    Data {
+        /// The address of the word.
+        address: char,
+        /// The index of the address, if any.
+        index: Option<I>,
+        /// The value of the word.
+        number: Decimal<S>,
+    },
+
+    /// See [`BlockBuilder::parameter_word`].
+    ParameterWord {
+        /// The address of the word.
+        address: char,
+        /// The index of the address, if any.
+        index: Option<I>,
+        /// The parameter that holds the value of the word.
+        parameter: I,
+    },
+
+    /// See [`BlockBuilder::parameter_assignment`].
+    ParameterAssignment {
+        /// The parameter.
+        parameter: I,
+        /// The value assigned to the parameter.
+        number: Decimal<S>,
+    },
+
+    /// See [`BlockBuilder::expression`].
+    Expression {
+        /// The token of the expression.
+        token: ExpressionToken<S, I>,
+    },
+
+    /// See [`BlockBuilder::expression_word`].
+    ExpressionWord {
+        /// The address of the word.
+        address: char,
+        /// The index of the address, if any.
+        index: Option<I>,
+    },
+
+    /// See [`BlockBuilder::expression_assignment`].
+    ExpressionAssignment {
+        /// The parameter.
+        parameter: I,
+    },
+
+    /// See [`BlockBuilder::o_word`].
+    OWord {
+        /// The number of the O-word.
+        number: I,
+        /// The keyword of the O-word.
+        keyword: OWordKeyword,
+    },
+
+    /// See [`BlockBuilder::o_word_argument`].
+    OWordArgument,
+
+    /// See [`BlockBuilder::checksum`].
+    Checksum {
+        /// The checksum.
+        checksum: u8,
+        /// `true` if the checksum matches the block.
+        valid: bool,
+    },
+
+    /// See [`BlockBuilder::end_block`].
+    EndBlock,
+
+    /// See [`BlockBuilder::abort_block`].
+    AbortBlock,
+
+    /// See [`BlockBuilder::comment_start`].
+    CommentStart {
+        /// The kind of comment.
+        kind: CommentKind,
+    },
+
+    /// See [`BlockBuilder::comment_char`].
+    CommentChar {
+        /// The character of the comment.
+        c: char,
+    },
+
+    /// See [`BlockBuilder::comment_end`].
+    CommentEnd,
+
+    /// See [`BlockBuilder::end_line`].
+    EndLine,
+
+    /// See [`BlockBuilder::word_start`].
+    #[cfg(feature = "position")]
+    WordStart {
+        /// The position of the first character of the word.
+        position: Position,
+    },
+}
+
+impl<S, I, N> Eq for Event<S, I, N>
+where
+    S: Eq + Significand,
+    I: Eq + Significand,
+    N: Eq + Significand,
+{
+}
+
+/// An iterator over the [`Event`]s of the [`BlockParser`] for a sequence of characters.
+///
+/// The events of each character are stored in a queue until they are consumed. `CAPACITY` is the
+/// size of this queue. A single character produces at most ten events (e.g., the closing bracket
+/// of an expression with operators of every precedence), so the default of 16 is always enough.
+///
+/// Each error of the [`BlockParser`] is returned after the events that preceded it. An error of
+/// [`ErrorKind::Capacity`] is also returned if the queue is full, in which case the rest of the
+/// events of the character are lost. With [`Config::with_recovery`], the iterator can continue
+/// after an error. Otherwise, the caller should stop.
+///
+/// [`Config::with_recovery`]: crate::Config::with_recovery
+#[derive(Debug)]
+pub struct Events<'a, T, S, I = S, N = I, const DEPTH: usize = 8, const CAPACITY: usize = 16>
+where
+    T: Iterator<Item = char>,
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    parser: &'a mut BlockParser<S, I, N, DEPTH>,
+    chars: T,
+    queue: Queue<S, I, N, CAPACITY>,
+    error: Option<Error<Infallible>>,
+    ended: bool,
+}
+
+impl<'a, T, S, I, N, const DEPTH: usize, const CAPACITY: usize>
+    Events<'a, T, S, I, N, DEPTH, CAPACITY>
+where
+    T: Iterator<Item = char>,
+    S: Significand,
+    I: Significand + TryFrom<S>,
+    N: Significand,
+{
+    /// Creates a new [`Events`] iterator that feeds `chars` to the `parser`.
+    ///
+    /// [`BlockParser::try_end`] is called after the last character.
+    pub fn new(parser: &'a mut BlockParser<S, I, N, DEPTH>, chars: T) -> Self {
+        Self {
+            parser,
+            chars,
+            queue: Queue::default(),
+            error: None,
+            ended: false,
+        }
+    }
+}
+
+impl<T, S, I, N, const DEPTH: usize, const CAPACITY: usize> Iterator
+    for Events<'_, T, S, I, N, DEPTH, CAPACITY>
+where
+    T: Iterator<Item = char>,
+    S: Significand,
+    I: Significand + TryFrom<S>,
+    N: Significand,
+{
+    type Item = Result<Event<S, I, N>, Error<Infallible>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.queue.pop() {
+                return Some(Ok(event));
+            }
+            if let Some(error) = self.error.take() {
+                return Some(Err(error));
+            }
+            if self.ended {
+                return None;
+            }
+            let result = if let Some(c) = self.chars.next() {
+                self.parser.try_feed_char(c, &mut self.queue)
+            } else {
+                self.ended = true;
+                self.parser.try_end(&mut self.queue)
+            };
+            self.error = result
+                .err()
+                .map(|error| error.map_block_builder_error(|Full| ErrorKind::Capacity));
+        }
+    }
+}
+
+impl<S, I, N, const DEPTH: usize> BlockParser<S, I, N, DEPTH>
+where
+    S: Significand,
+    I: Significand + TryFrom<S>,
+    N: Significand,
+{
+    /// Returns an iterator over the [`Event`]s for `chars`, as an alternative to implementing the
+    /// [`BlockBuilder`] trait.
+    ///
+    /// See [`Events`].
+    ///
+    /// ```
+    /// use gcode::{BlockParser, Decimal, Event};
+    ///
+    /// let mut parser = BlockParser::<i32>::default();
+    /// for event in parser.events("G1 X1.5\n".chars()) {
+    ///     match event.unwrap() {
+    ///         Event::GCode { number } => assert_eq!(number, Decimal::new(1, 0)),
+    ///         Event::Data { address, number, .. } => {
+    ///             assert_eq!((address, number), ('X', Decimal::new(15, 1)));
+    ///         }
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    pub fn events<T>(&mut self, chars: T) -> Events<'_, T::IntoIter, S, I, N, DEPTH>
+    where
+        T: IntoIterator<Item = char>,
+    {
+        Events::new(self, chars.into_iter())
+    }
+}
+
+/// The error of a full [`Queue`].
+#[derive(Debug)]
+struct Full;
+
+/// A [`BlockBuilder`] that stores the events of a single character.
+#[derive(Debug)]
+struct Queue<S, I, N, const CAPACITY: usize>
+where
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    events: [Option<Event<S, I, N>>; CAPACITY],
+    len: usize,
+    next: usize,
+}
+
+impl<S, I, N, const CAPACITY: usize> Default for Queue<S, I, N, CAPACITY>
+where
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    fn default() -> Self {
+        Self {
+            events: [None; CAPACITY],
+            len: 0,
+            next: 0,
+        }
+    }
+}
+
+impl<S, I, N, const CAPACITY: usize> Queue<S, I, N, CAPACITY>
+where
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    fn push(&mut self, event: Event<S, I, N>) -> Result<(), Full> {
+        let slot = self.events.get_mut(self.len).ok_or(Full)?;
+        *slot = Some(event);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Returns the next event. The queue is empty again after the last one.
+    fn pop(&mut self) -> Option<Event<S, I, N>> {
+        let event = self.events[..self.len].get(self.next).copied().flatten();
+        if event.is_some() {
+            self.next += 1;
+        } else {
+            self.len = 0;
+            self.next = 0;
+        }
+        event
+    }
+}
+
+impl<S, I, N, const CAPACITY: usize> BlockBuilder for Queue<S, I, N, CAPACITY>
+where
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    type Error = Full;
+    type Significand = S;
+    type Index = I;
+    type SequenceNumber = N;
+
+    fn program_start(&mut self) -> Result<(), Self::Error> {
+        self.push(Event::ProgramStart)
+    }
+
+    fn sequence_number(&mut self, alignment: bool, number: Decimal<N>) -> Result<(), Self::Error> {
+        self.push(Event::SequenceNumber { alignment, number })
+    }
+
+    fn g_code(&mut self, number: Decimal<S>) -> Result<(), Self::Error> {
+        self.push(Event::GCode { number })
+    }
+
+    fn m_code(&mut self, number: Decimal<S>) -> Result<(), Self::Error> {
+        self.push(Event::MCode { number })
+    }
+
+    fn data(
+        &mut self,
+        address: char,
+        index: Option<I>,
+        number: Decimal<S>,
+    ) -> Result<(), Self::Error> {
+        self.push(Event::Data {
+            address,
+            index,
+            number,
+        })
+    }
+
+    fn parameter_word(
+        &mut self,
+        address: char,
+        index: Option<I>,
+        parameter: I,
+    ) -> Result<(), Self::Error> {
+        self.push(Event::ParameterWord {
+            address,
+            index,
+            parameter,
+        })
+    }
+
+    fn parameter_assignment(
+        &mut self,
+        parameter: I,
+        number: Decimal<S>,
+    ) -> Result<(), Self::Error> {
+        self.push(Event::ParameterAssignment { parameter, number })
+    }
+
+    fn expression(&mut self, token: ExpressionToken<S, I>) -> Result<(), Self::Error> {
+        self.push(Event::Expression { token })
+    }
+
+    fn expression_word(&mut self, address: char, index: Option<I>) -> Result<(), Self::Error> {
+        self.push(Event::ExpressionWord { address, index })
+    }
+
+    fn expression_assignment(&mut self, parameter: I) -> Result<(), Self::Error> {
+        self.push(Event::ExpressionAssignment { parameter })
+    }
+
+    fn o_word(&mut self, number: I, keyword: OWordKeyword) -> Result<(), Self::Error> {
+        self.push(Event::OWord { number, keyword })
+    }
+
+    fn o_word_argument(&mut self) -> Result<(), Self::Error> {
+        self.push(Event::OWordArgument)
+    }
+
+    fn checksum(&mut self, checksum: u8, valid: bool) -> Result<(), Self::Error> {
+        self.push(Event::Checksum { checksum, valid })
+    }
+
+    fn end_block(&mut self) -> Result<(), Self::Error> {
+        self.push(Event::EndBlock)
+    }
+
+    fn abort_block(&mut self) -> Result<(), Self::Error> {
+        self.push(Event::AbortBlock)
+    }
+
+    fn comment_start(&mut self, kind: CommentKind) -> Result<(), Self::Error> {
+        self.push(Event::CommentStart { kind })
+    }
+
+    fn comment_char(&mut self, c: char) -> Result<(), Self::Error> {
+        self.push(Event::CommentChar { c })
+    }
+
+    fn comment_end(&mut self) -> Result<(), Self::Error> {
+        self.push(Event::CommentEnd)
+    }
+
+    fn end_line(&mut self) -> Result<(), Self::Error> {
+        self.push(Event::EndLine)
+    }
+
+    #[cfg(feature = "position")]
+    fn word_start(&mut self, position: Position) -> Result<(), Self::Error> {
+        self.push(Event::WordStart { position })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::config::Config;
+    use crate::expression::BinaryOperator;
+
+    extern crate std;
+    use std::vec::Vec;
+
+    type Results = Vec<Result<Event<i32, u8, u32>, Error<Infallible>>>;
+
+    fn events(config: Config, s: &str) -> Results {
+        let mut parser = BlockParser::<i32, u8, u32>::new(config);
+        parser
+            .events(s.chars())
+            .filter(|result| !is_word_start(result))
+            .collect()
+    }
+
+    fn is_word_start<S, I, N, E>(result: &Result<Event<S, I, N>, E>) -> bool
+    where
+        S: Significand,
+        I: Significand,
+        N: Significand,
+    {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "position")] {
+                matches!(result, Ok(Event::WordStart { .. }))
+            } else {
+                let _ = result;
+                false
+            }
+        }
+    }
+
+    #[test]
+    fn events_block() {
+        assert_eq!(
+            events(Config::default(), "N10 G1 X1.5 (move)\n"),
+            [
+                Ok(Event::SequenceNumber {
+                    alignment: false,
+                    number: Decimal::new(10, 0),
+                }),
+                Ok(Event::GCode {
+                    number: Decimal::new(1, 0),
+                }),
+                Ok(Event::CommentStart {
+                    kind: CommentKind::ControlOut,
+                }),
+                Ok(Event::CommentChar { c: 'm' }),
+                Ok(Event::CommentChar { c: 'o' }),
+                Ok(Event::CommentChar { c: 'v' }),
+                Ok(Event::CommentChar { c: 'e' }),
+                Ok(Event::CommentEnd),
+                Ok(Event::Data {
+                    address: 'X',
+                    index: None,
+                    number: Decimal::new(15, 1),
+                }),
+                Ok(Event::EndBlock),
+                Ok(Event::EndLine),
+            ]
+        );
+    }
+
+    #[test]
+    fn events_end() {
+        assert_eq!(
+            events(Config::default(), "M3"),
+            [
+                Ok(Event::MCode {
+                    number: Decimal::new(3, 0),
+                }),
+                Ok(Event::EndBlock),
+                Ok(Event::EndLine),
+            ]
+        );
+        assert_eq!(events(Config::default(), ""), []);
+    }
+
+    #[test]
+    fn events_expression() {
+        let config = Config::default().with_expressions(true);
+        let number = |n| {
+            Ok(Event::Expression {
+                token: ExpressionToken::Number(Decimal::new(n, 0)),
+            })
+        };
+        let token = |token| Ok(Event::Expression { token });
+        assert_eq!(
+            events(config, "X[1 + 2]\n"),
+            [
+                token(ExpressionToken::Open),
+                number(1),
+                token(ExpressionToken::Operator(BinaryOperator::Add)),
+                number(2),
+                token(ExpressionToken::ApplyOperator(BinaryOperator::Add)),
+                token(ExpressionToken::Close),
+                Ok(Event::ExpressionWord {
+                    address: 'X',
+                    index: None,
+                }),
+                Ok(Event::EndBlock),
+                Ok(Event::EndLine),
+            ]
+        );
+    }
+
+    #[test]
+    fn events_close() {
+        let config = Config::default().with_expressions(true);
+        let events = events(config, "X[1 AND 2 EQ 3 + 4 * 5 ** 6]");
+        assert!(events.iter().all(Result::is_ok));
+        assert_eq!(events.len(), 21);
+    }
+
+    #[test]
+    fn events_error() {
+        let config = Config::default().with_recovery(true);
+        let events = events(config, "G1 $\nG2\n");
+        assert_eq!(
+            events[0],
+            Ok(Event::GCode {
+                number: Decimal::new(1, 0),
+            })
+        );
+        assert_eq!(
+            events[1].as_ref().map_err(Error::kind),
+            Err(&ErrorKind::InvalidCharacter)
+        );
+        assert_eq!(
+            events[2..],
+            [
+                Ok(Event::AbortBlock),
+                Ok(Event::GCode {
+                    number: Decimal::new(2, 0),
+                }),
+                Ok(Event::EndBlock),
+                Ok(Event::EndLine),
+            ]
+        );
+    }
+
+    #[test]
+    fn events_capacity() {
+        let mut parser = BlockParser::<i32>::default();
+        let events = Events::<_, _, _, _, 8, 2>::new(&mut parser, "G1\n".chars());
+        let events = events
+            .filter(|result| !is_word_start(result))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events[..2],
+            [
+                Ok(Event::GCode {
+                    number: Decimal::new(1, 0),
+                }),
+                Ok(Event::EndBlock),
+            ]
+        );
+        assert_eq!(
+            events[2].as_ref().map_err(Error::kind),
+            Err(&ErrorKind::Capacity)
+        );
+        assert_eq!(events.len(), 3);
+    }
+}
@@ -0,0 +1,629 @@
+use core::fmt::Debug;
+
+use crate::block::{BlockBuilder, Error};
+use crate::decimal;
+use crate::decimal::{Decimal, DecimalParser};
+use crate::letters::Letters;
+use crate::sign::Sign;
+use crate::significand::Significand;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// A token of an expression (e.g., `[#1 + 2.5 * COS[30]]`.)
+///
+/// The [`BlockParser`] does not evaluate expressions. It reports each expression to the
+/// [`BlockBuilder`] as a stream of tokens. The stream contains two interleaved views of the same
+/// expression:
+///
+/// *   The operands ([`Number`] and [`Parameter`]) and the syntax tokens ([`Open`], [`Close`],
+///     [`Operator`], [`Negate`], and [`Function`]) appear in the same order as in the text. A
+///     consumer that only needs to reproduce the text (e.g., to write it back out) can ignore
+///     [`ApplyOperator`], [`ApplyNegate`], and [`ApplyFunction`].
+///
+/// *   The operands and the apply tokens ([`ApplyOperator`], [`ApplyNegate`], and
+///     [`ApplyFunction`]) appear in postfix ("reverse Polish") order. The parser has already taken care of brackets and
+///     operator precedence. A consumer that evaluates the expression can push each operand onto a
+///     stack, pop the arguments of each apply token from the stack, and push the result. It can
+///     ignore the syntax tokens.
+///
+/// For example, `[1 + 2 * 3]` produces the tokens `Open`, `Number(1)`, `Operator(Add)`,
+/// `Number(2)`, `Operator(Multiply)`, `Number(3)`, `ApplyOperator(Multiply)`,
+/// `ApplyOperator(Add)`, and `Close`.
+///
+/// A unary minus applies to the operand that immediately follows it, before any binary operator.
+/// For example, `[-#1 ** 2]` is the square of `-#1`. A minus sign before a number is part of the
+/// [`Number`], but a minus sign before any other operand produces [`Negate`] and, after the
+/// operand, [`ApplyNegate`]. A unary plus produces no token.
+///
+/// [`BlockParser`]: crate::BlockParser
+/// [`Number`]: ExpressionToken::Number
+/// [`Parameter`]: ExpressionToken::Parameter
+/// [`Open`]: ExpressionToken::Open
+/// [`Close`]: ExpressionToken::Close
+/// [`Operator`]: ExpressionToken::Operator
+/// [`Negate`]: ExpressionToken::Negate
+/// [`Function`]: ExpressionToken::Function
+/// [`ApplyOperator`]: ExpressionToken::ApplyOperator
+/// [`ApplyNegate`]: ExpressionToken::ApplyNegate
+/// [`ApplyFunction`]: ExpressionToken::ApplyFunction
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum ExpressionToken<S, I = S>
+where
+    S: Significand,
+    I: Significand,
+{
+    /// An opening bracket (`[`.)
+    Open,
+
+    /// A closing bracket (`]`.)
+    Close,
+
+    /// A number.
+    Number(Decimal<S>),
+
+    /// The value of a numbered parameter (e.g., `#5`.)
+    Parameter(I),
+
+    /// A binary operator, in text order.
+    Operator(BinaryOperator),
+
+    /// A unary minus before an operand that is not a number (e.g., `-#1` or `-[1 + 2]`), in text
+    /// order.
+    Negate,
+
+    /// The name of a function, in text order. The argument follows in brackets.
+    Function(Function),
+
+    /// A binary operator, in postfix order. The operator applies to the top two values of the
+    /// stack. The top value is the right-hand side.
+    ApplyOperator(BinaryOperator),
+
+    /// A unary minus, in postfix order. It negates the top value of the stack.
+    ApplyNegate,
+
+    /// A function, in postfix order. The function applies to the top value of the stack, or the
+    /// top two values for [`Function::Atan`].
+    ApplyFunction(Function),
+}
+
+impl<S, I> Eq for ExpressionToken<S, I>
+where
+    S: Eq + Significand,
+    I: Eq + Significand,
+{
+}
+
+/// The binary operators.
+///
+/// The operators are listed from highest to lowest precedence. Operators with equal precedence
+/// are evaluated from left to right.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum BinaryOperator {
+    /// `**`
+    Power,
+
+    /// `*`
+    Multiply,
+
+    /// `/`
+    Divide,
+
+    /// `MOD`
+    Modulo,
+
+    /// `+`
+    Add,
+
+    /// `-`
+    Subtract,
+
+    /// `EQ`
+    Equal,
+
+    /// `NE`
+    NotEqual,
+
+    /// `GT`
+    Greater,
+
+    /// `GE`
+    GreaterOrEqual,
+
+    /// `LT`
+    Less,
+
+    /// `LE`
+    LessOrEqual,
+
+    /// `AND`
+    And,
+
+    /// `OR`
+    Or,
+
+    /// `XOR`
+    ExclusiveOr,
+}
+
+impl BinaryOperator {
+    const fn precedence(self) -> u8 {
+        match self {
+            Self::Power => 4,
+            Self::Multiply | Self::Divide | Self::Modulo => 3,
+            Self::Add | Self::Subtract => 2,
+            Self::Equal
+            | Self::NotEqual
+            | Self::Greater
+            | Self::GreaterOrEqual
+            | Self::Less
+            | Self::LessOrEqual => 1,
+            Self::And | Self::Or | Self::ExclusiveOr => 0,
+        }
+    }
+
+    /// Returns the text of the operator, in upper case.
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Power => "**",
+            Self::Multiply => "*",
+            Self::Divide => "/",
+            Self::Modulo => "MOD",
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::Equal => "EQ",
+            Self::NotEqual => "NE",
+            Self::Greater => "GT",
+            Self::GreaterOrEqual => "GE",
+            Self::Less => "LT",
+            Self::LessOrEqual => "LE",
+            Self::And => "AND",
+            Self::Or => "OR",
+            Self::ExclusiveOr => "XOR",
+        }
+    }
+
+    fn from_name(name: &[u8]) -> Option<Self> {
+        match name {
+            b"MOD" => Some(Self::Modulo),
+            b"EQ" => Some(Self::Equal),
+            b"NE" => Some(Self::NotEqual),
+            b"GT" => Some(Self::Greater),
+            b"GE" => Some(Self::GreaterOrEqual),
+            b"LT" => Some(Self::Less),
+            b"LE" => Some(Self::LessOrEqual),
+            b"AND" => Some(Self::And),
+            b"OR" => Some(Self::Or),
+            b"XOR" => Some(Self::ExclusiveOr),
+            _ => None,
+        }
+    }
+}
+
+/// The functions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum Function {
+    /// `ABS[x]`
+    Abs,
+
+    /// `ACOS[x]`, in degrees.
+    Acos,
+
+    /// `ASIN[x]`, in degrees.
+    Asin,
+
+    /// `ATAN[y]/[x]`, in degrees. This is the only function with two arguments.
+    Atan,
+
+    /// `COS[x]`, in degrees.
+    Cos,
+
+    /// `EXP[x]`
+    Exp,
+
+    /// `FIX[x]`, rounds down.
+    Fix,
+
+    /// `FUP[x]`, rounds up.
+    Fup,
+
+    /// `LN[x]`
+    Ln,
+
+    /// `ROUND[x]`, rounds to the nearest integer.
+    Round,
+
+    /// `SIN[x]`, in degrees.
+    Sin,
+
+    /// `SQRT[x]`
+    Sqrt,
+
+    /// `TAN[x]`, in degrees.
+    Tan,
+}
+
+impl Function {
+    /// Returns the name of the function, in upper case.
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Abs => "ABS",
+            Self::Acos => "ACOS",
+            Self::Asin => "ASIN",
+            Self::Atan => "ATAN",
+            Self::Cos => "COS",
+            Self::Exp => "EXP",
+            Self::Fix => "FIX",
+            Self::Fup => "FUP",
+            Self::Ln => "LN",
+            Self::Round => "ROUND",
+            Self::Sin => "SIN",
+            Self::Sqrt => "SQRT",
+            Self::Tan => "TAN",
+        }
+    }
+
+    fn from_name(name: &[u8]) -> Option<Self> {
+        match name {
+            b"ABS" => Some(Self::Abs),
+            b"ACOS" => Some(Self::Acos),
+            b"ASIN" => Some(Self::Asin),
+            b"ATAN" => Some(Self::Atan),
+            b"COS" => Some(Self::Cos),
+            b"EXP" => Some(Self::Exp),
+            b"FIX" => Some(Self::Fix),
+            b"FUP" => Some(Self::Fup),
+            b"LN" => Some(Self::Ln),
+            b"ROUND" => Some(Self::Round),
+            b"SIN" => Some(Self::Sin),
+            b"SQRT" => Some(Self::Sqrt),
+            b"TAN" => Some(Self::Tan),
+            _ => None,
+        }
+    }
+}
+
+/// A parser for bracketed expressions.
+///
+/// The parser converts the expression to postfix order with the "shunting yard" algorithm. The
+/// stack of pending brackets, functions, and operators has a fixed depth of `DEPTH`.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct ExpressionParser<S, I, const DEPTH: usize>
+where
+    S: Significand,
+    I: Significand,
+{
+    state: State,
+    stack: [Frame; DEPTH],
+    depth: usize,
+    decimal_parser: DecimalParser<S>,
+    parameter_parser: DecimalParser<I>,
+    name: Letters<5>,
+}
+
+impl<S, I, const DEPTH: usize> Default for ExpressionParser<S, I, DEPTH>
+where
+    S: Significand,
+    I: Significand,
+{
+    fn default() -> Self {
+        Self {
+            state: State::Operand,
+            stack: [Frame::Open; DEPTH],
+            depth: 0,
+            decimal_parser: DecimalParser::default(),
+            parameter_parser: DecimalParser::default(),
+            name: Letters::default(),
+        }
+    }
+}
+
+impl<S, I, const DEPTH: usize> ExpressionParser<S, I, DEPTH>
+where
+    S: Significand,
+    I: Significand,
+{
+    /// Try to feed a single character to the parser. The first character must be `[`.
+    ///
+    /// Returns `true` when the closing bracket of the expression has been consumed.
+    pub fn try_feed<B>(&mut self, c: char, builder: &mut B) -> Result<bool, Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I>,
+    {
+        match self.state {
+            State::Operand => self.parse_operand(c, builder),
+            State::Sign(sign) => {
+                if '.' == c || c.is_ascii_digit() {
+                    // The sign is part of the number.
+                    self.state = State::Number;
+                    self.decimal_parser = DecimalParser::default();
+                    if Sign::Negative == sign {
+                        self.decimal_parser.try_feed('-')?;
+                    }
+                    self.decimal_parser.try_feed(c)?;
+                    Ok(false)
+                } else {
+                    if Sign::Negative == sign {
+                        self.push(Frame::Negate)?;
+                        Self::emit(ExpressionToken::Negate, builder)?;
+                    }
+                    self.parse_operand(c, builder)
+                }
+            }
+            State::Number => match self.decimal_parser.try_feed(c) {
+                Ok(()) => Ok(false),
+                Err(decimal::Error::InvalidCharacter) => {
+                    let number = self.decimal_parser.try_end()?;
+                    Self::emit(ExpressionToken::Number(number), builder)?;
+                    self.end_operand(builder)?;
+                    self.parse_operator(c, builder)
+                }
+                Err(e) => Err(e.into()),
+            },
+            State::Parameter => {
+                if c.is_ascii_digit() {
+                    self.parameter_parser.try_feed(c)?;
+                    Ok(false)
+                } else {
+                    let parameter = self.parameter_parser.try_end()?;
+                    assert_eq!(parameter.negative_exponent(), 0);
+                    let token = ExpressionToken::Parameter(parameter.significand());
+                    Self::emit(token, builder)?;
+                    self.end_operand(builder)?;
+                    self.parse_operator(c, builder)
+                }
+            }
+            State::FunctionName => {
+                if '[' == c {
+                    let function = Function::from_name(self.name.as_bytes())
+                        .ok_or_else(Error::new_invalid_character)?;
+                    Self::emit(ExpressionToken::Function(function), builder)?;
+                    self.open(Frame::Function(function), builder)
+                } else if self.name.try_push(c) {
+                    Ok(false)
+                } else {
+                    Err(Error::new_invalid_character())
+                }
+            }
+            State::Operator => self.parse_operator(c, builder),
+            State::OperatorName => {
+                if self.name.try_push(c) {
+                    if let Some(operator) = BinaryOperator::from_name(self.name.as_bytes()) {
+                        self.binary_operator(operator, builder)?;
+                    }
+                    Ok(false)
+                } else {
+                    Err(Error::new_invalid_character())
+                }
+            }
+            State::Star => {
+                if '*' == c {
+                    self.binary_operator(BinaryOperator::Power, builder)?;
+                    Ok(false)
+                } else {
+                    self.binary_operator(BinaryOperator::Multiply, builder)?;
+                    self.parse_operand(c, builder)
+                }
+            }
+            State::AtanDivide => {
+                if '/' == c {
+                    Self::emit(ExpressionToken::Operator(BinaryOperator::Divide), builder)?;
+                    self.state = State::AtanOpen;
+                    Ok(false)
+                } else {
+                    Err(Error::new_invalid_character())
+                }
+            }
+            State::AtanOpen => {
+                if '[' == c {
+                    self.open(Frame::AtanDenominator, builder)
+                } else {
+                    Err(Error::new_invalid_character())
+                }
+            }
+        }
+    }
+
+    fn parse_operand<B>(&mut self, c: char, builder: &mut B) -> Result<bool, Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I>,
+    {
+        match c {
+            '[' => self.open(Frame::Open, builder),
+            '+' => {
+                self.state = State::Sign(Sign::Positive);
+                Ok(false)
+            }
+            '-' => {
+                self.state = State::Sign(Sign::Negative);
+                Ok(false)
+            }
+            '#' => {
+                self.state = State::Parameter;
+                self.parameter_parser = DecimalParser::default();
+                Ok(false)
+            }
+            'A'..='Z' | 'a'..='z' => {
+                self.state = State::FunctionName;
+                self.name = Letters::default();
+                self.name.try_push(c);
+                Ok(false)
+            }
+            c => {
+                self.state = State::Number;
+                self.decimal_parser = DecimalParser::default();
+                self.decimal_parser.try_feed(c)?;
+                Ok(false)
+            }
+        }
+    }
+
+    fn parse_operator<B>(&mut self, c: char, builder: &mut B) -> Result<bool, Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I>,
+    {
+        self.state = State::Operator;
+        match c {
+            ']' => self.close(builder),
+            '*' => {
+                self.state = State::Star;
+                Ok(false)
+            }
+            '+' => {
+                self.binary_operator(BinaryOperator::Add, builder)?;
+                Ok(false)
+            }
+            '-' => {
+                self.binary_operator(BinaryOperator::Subtract, builder)?;
+                Ok(false)
+            }
+            '/' => {
+                self.binary_operator(BinaryOperator::Divide, builder)?;
+                Ok(false)
+            }
+            'A'..='Z' | 'a'..='z' => {
+                self.state = State::OperatorName;
+                self.name = Letters::default();
+                self.name.try_push(c);
+                Ok(false)
+            }
+            _ => Err(Error::new_invalid_character()),
+        }
+    }
+
+    fn binary_operator<B>(
+        &mut self,
+        operator: BinaryOperator,
+        builder: &mut B,
+    ) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I>,
+    {
+        while let Some(Frame::Operator(top)) = self.peek() {
+            if top.precedence() < operator.precedence() {
+                break;
+            }
+            self.depth -= 1;
+            Self::emit(ExpressionToken::ApplyOperator(top), builder)?;
+        }
+        self.push(Frame::Operator(operator))?;
+        self.state = State::Operand;
+        Self::emit(ExpressionToken::Operator(operator), builder)
+    }
+
+    fn open<B>(&mut self, frame: Frame, builder: &mut B) -> Result<bool, Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I>,
+    {
+        self.push(frame)?;
+        self.state = State::Operand;
+        Self::emit(ExpressionToken::Open, builder)?;
+        Ok(false)
+    }
+
+    fn close<B>(&mut self, builder: &mut B) -> Result<bool, Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I>,
+    {
+        loop {
+            let frame = self.peek().ok_or_else(Error::new_invalid_character)?;
+            self.depth -= 1;
+            match frame {
+                Frame::Operator(operator) => {
+                    Self::emit(ExpressionToken::ApplyOperator(operator), builder)?;
+                }
+                Frame::Open => {
+                    Self::emit(ExpressionToken::Close, builder)?;
+                    break;
+                }
+                Frame::Function(Function::Atan) => {
+                    Self::emit(ExpressionToken::Close, builder)?;
+                    self.state = State::AtanDivide;
+                    return Ok(false);
+                }
+                Frame::Function(function) => {
+                    Self::emit(ExpressionToken::Close, builder)?;
+                    Self::emit(ExpressionToken::ApplyFunction(function), builder)?;
+                    break;
+                }
+                Frame::AtanDenominator => {
+                    Self::emit(ExpressionToken::Close, builder)?;
+                    Self::emit(ExpressionToken::ApplyFunction(Function::Atan), builder)?;
+                    break;
+                }
+                // An operand always follows a unary minus, and it ends before the bracket.
+                Frame::Negate => return Err(Error::new_invalid_character()),
+            }
+        }
+        self.end_operand(builder)?;
+        self.state = State::Operator;
+        Ok(0 == self.depth)
+    }
+
+    /// Applies the unary minus signs before the operand that just ended, if any.
+    fn end_operand<B>(&mut self, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I>,
+    {
+        while matches!(self.peek(), Some(Frame::Negate)) {
+            self.depth -= 1;
+            Self::emit(ExpressionToken::ApplyNegate, builder)?;
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<Frame> {
+        self.depth
+            .checked_sub(1)
+            .and_then(|top| self.stack.get(top))
+            .copied()
+    }
+
+    fn push<E>(&mut self, frame: Frame) -> Result<(), Error<E>> {
+        let slot = self
+            .stack
+            .get_mut(self.depth)
+            .ok_or_else(Error::new_capacity)?;
+        *slot = frame;
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn emit<B>(token: ExpressionToken<S, I>, builder: &mut B) -> Result<(), Error<B::Error>>
+    where
+        B: BlockBuilder<Significand = S, Index = I>,
+    {
+        builder
+            .expression(token)
+            .map_err(Error::new_block_builder_error)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+enum State {
+    Operand,
+    Sign(Sign),
+    Number,
+    Parameter,
+    FunctionName,
+    Operator,
+    OperatorName,
+    Star,
+    AtanDivide,
+    AtanOpen,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+enum Frame {
+    Open,
+    Function(Function),
+    AtanDenominator,
+    Operator(BinaryOperator),
+    Negate,
+}
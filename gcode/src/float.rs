@@ -0,0 +1,164 @@
+use core::fmt::Write;
+use core::str::FromStr;
+
+use crate::decimal::{Decimal, DecimalParser};
+use crate::digits::Digits;
+use crate::significand::Significand;
+
+impl<S> Decimal<S>
+where
+    S: Significand,
+{
+    /// Converts the [`Decimal`] number to the nearest `f32`.
+    ///
+    /// The conversion is correctly rounded. It does not round twice (e.g., by dividing the
+    /// significand by a power of ten.)
+    #[must_use]
+    pub fn to_f32(self) -> f32 {
+        self.to_float()
+    }
+
+    /// Converts the [`Decimal`] number to the nearest `f64`.
+    ///
+    /// The conversion is correctly rounded. It does not round twice (e.g., by dividing the
+    /// significand by a power of ten.)
+    #[must_use]
+    pub fn to_f64(self) -> f64 {
+        self.to_float()
+    }
+
+    /// Converts an `f64` to a [`Decimal`] number with at most `max_negative_exponent` digits right
+    /// of the decimal point.
+    ///
+    /// The result is the shortest decimal number that converts back to the same `f64` (e.g.,
+    /// `0.1` rather than `0.1000000000000000055511151231257827`) if it has few enough digits.
+    /// Otherwise, the `f64` is rounded to `max_negative_exponent` digits. Returns `None` if the
+    /// `f64` is not finite or if the result does not fit in the significand.
+    #[must_use]
+    pub fn try_from_f64(value: f64, max_negative_exponent: u32) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        let mut buffer = Buffer::default();
+        let shortest = write!(buffer, "{value}").is_ok()
+            && fraction_digits(buffer.as_str()) <= usize::try_from(max_negative_exponent).ok()?;
+        if !shortest {
+            let precision = usize::try_from(max_negative_exponent).ok()?;
+            buffer = Buffer::default();
+            write!(buffer, "{value:.precision$}").ok()?;
+        }
+        let mut parser = DecimalParser::default();
+        for c in buffer.as_str().chars() {
+            parser.try_feed(c).ok()?;
+        }
+        parser.try_end().ok()
+    }
+
+    fn to_float<F>(self) -> F
+    where
+        F: Default + FromStr,
+    {
+        // The core library parses floats with correct rounding. The buffer is always large enough
+        // and the text is always a valid float.
+        let mut buffer = Buffer::default();
+        let sign = if self.significand().is_negative() {
+            "-"
+        } else {
+            ""
+        };
+        let digits = Digits::new(self.significand());
+        let _ = write!(
+            buffer,
+            "{sign}{}e-{}",
+            digits.as_str(),
+            self.negative_exponent()
+        );
+        buffer.as_str().parse().unwrap_or_default()
+    }
+}
+
+/// A text buffer long enough for any number that fits in a significand.
+type Buffer = crate::buffer::Buffer<48>;
+
+/// Returns the number of digits right of the decimal point.
+fn fraction_digits(text: &str) -> usize {
+    text.find('.').map_or(0, |index| text.len() - index - 1)
+}
+
+// The conversions are correctly rounded, so the tests compare floats exactly.
+#[allow(clippy::float_cmp)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_f64() {
+        assert_eq!(Decimal::new(-25_i32, 3).to_f64(), -0.025);
+        assert_eq!(Decimal::new(12_i32, 0).to_f64(), 12.0);
+        assert_eq!(Decimal::new(0_i32, 0).to_f64(), 0.0);
+    }
+
+    #[test]
+    fn to_f64_correctly_rounded() {
+        // Dividing by a power of ten would round twice: once for the significand or the power of
+        // ten, and once for the quotient.
+        let decimal = Decimal::new(9_007_199_254_740_993_u64, 2);
+        assert_eq!(decimal.to_f64(), 90_071_992_547_409.94);
+        assert_eq!(Decimal::new(1_i32, 23).to_f64(), 1e-23);
+        let decimal = Decimal::new(i64::MIN, 18);
+        assert_eq!(decimal.to_f64(), -9.223_372_036_854_776);
+    }
+
+    #[test]
+    fn to_f32() {
+        assert_eq!(Decimal::new(1_i8, 1).to_f32(), 0.1);
+        assert_eq!(
+            Decimal::new(u64::MAX, 0).to_f32(),
+            18_446_744_073_709_551_615.0
+        );
+    }
+
+    #[test]
+    fn to_f64_large_exponent() {
+        assert_eq!(Decimal::new(5_i32, u32::MAX).to_f64(), 0.0);
+    }
+
+    #[test]
+    fn try_from_f64_shortest() {
+        assert_eq!(Decimal::try_from_f64(0.1, 6), Some(Decimal::new(1_i32, 1)));
+        assert_eq!(
+            Decimal::try_from_f64(-2.5, 6),
+            Some(Decimal::new(-25_i32, 1))
+        );
+        assert_eq!(
+            Decimal::try_from_f64(12.0, 6),
+            Some(Decimal::new(12_i32, 0))
+        );
+        assert_eq!(Decimal::try_from_f64(-0.0, 6), Some(Decimal::new(0_i32, 0)));
+    }
+
+    #[test]
+    fn try_from_f64_rounded() {
+        assert_eq!(
+            Decimal::try_from_f64(1.0 / 3.0, 4),
+            Some(Decimal::new(3_333_i32, 4))
+        );
+        assert_eq!(
+            Decimal::try_from_f64(0.125, 1),
+            Some(Decimal::new(1_i32, 1))
+        );
+        assert_eq!(
+            Decimal::try_from_f64(1e-300, 3),
+            Some(Decimal::new(0_i32, 0))
+        );
+    }
+
+    #[test]
+    fn try_from_f64_none() {
+        assert_eq!(Decimal::<i32>::try_from_f64(f64::NAN, 3), None);
+        assert_eq!(Decimal::<i32>::try_from_f64(f64::INFINITY, 3), None);
+        assert_eq!(Decimal::<i32>::try_from_f64(1e10, 3), None);
+        assert_eq!(Decimal::<u32>::try_from_f64(-1.0, 3), None);
+        assert_eq!(Decimal::<i64>::try_from_f64(1e300, 3), None);
+    }
+}
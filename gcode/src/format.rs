@@ -0,0 +1,360 @@
+use core::fmt::{self, Alignment, Display, Formatter, Write};
+
+use crate::decimal::{Decimal, Rounded, Rounding};
+use crate::digits::Digits;
+use crate::significand::Significand;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// Formats a [`Decimal`] number with a fixed number of digits right of the decimal point.
+///
+/// See [`Decimal::fixed`].
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Fixed<S>
+where
+    S: Significand,
+{
+    decimal: Decimal<S>,
+    negative_exponent: u32,
+    rounding: Rounding,
+}
+
+impl<S> Decimal<S>
+where
+    S: Significand,
+{
+    /// Returns a formatter that writes the [`Decimal`] number with exactly `negative_exponent`
+    /// digits right of the decimal point, padding with zeros or rounding as necessary.
+    ///
+    /// It implements [`Display`] and, with the `ufmt` feature, `ufmt::uDisplay`.
+    #[must_use]
+    pub const fn fixed(self, negative_exponent: u32, rounding: Rounding) -> Fixed<S> {
+        Fixed {
+            decimal: self,
+            negative_exponent,
+            rounding,
+        }
+    }
+
+    /// Writes the shortest text of the [`Decimal`] number, without trailing zeros right of the
+    /// decimal point.
+    pub(crate) fn write_minimal<E, W>(self, write_str: W) -> Result<(), E>
+    where
+        W: FnMut(&str) -> Result<(), E>,
+    {
+        let digits = Digits::new(self.significand());
+        let mut text = digits.as_str();
+        let mut negative_exponent = self.negative_exponent();
+        while negative_exponent > 0 && text.len() > 1 && text.ends_with('0') {
+            text = &text[..text.len() - 1];
+            negative_exponent -= 1;
+        }
+        if self.significand().is_zero() {
+            negative_exponent = 0;
+        }
+        write_decimal(self.significand(), text, negative_exponent, 0, write_str)
+    }
+}
+
+impl<S> Fixed<S>
+where
+    S: Significand,
+{
+    pub(crate) fn write<E, W>(self, write_str: W) -> Result<(), E>
+    where
+        W: FnMut(&str) -> Result<(), E>,
+    {
+        let decimal = self.decimal;
+        if let Some(pad) = self
+            .negative_exponent
+            .checked_sub(decimal.negative_exponent())
+        {
+            let digits = Digits::new(decimal.significand());
+            let negative_exponent = decimal.negative_exponent();
+            write_decimal(
+                decimal.significand(),
+                digits.as_str(),
+                negative_exponent,
+                pad,
+                write_str,
+            )
+        } else {
+            // Rounding to fewer digits never overflows.
+            let significand = decimal
+                .to_scaled(self.negative_exponent, self.rounding)
+                .map_or_else(|| decimal.significand(), Rounded::value);
+            let digits = Digits::new(significand);
+            write_decimal(
+                significand,
+                digits.as_str(),
+                self.negative_exponent,
+                0,
+                write_str,
+            )
+        }
+    }
+}
+
+/// Writes a number with the specified digits and negative exponent, followed by `pad` zeros right
+/// of the decimal point.
+fn write_decimal<S, E, W>(
+    significand: S,
+    digits: &str,
+    negative_exponent: u32,
+    pad: u32,
+    mut write_str: W,
+) -> Result<(), E>
+where
+    S: Significand,
+    W: FnMut(&str) -> Result<(), E>,
+{
+    if significand.is_negative() {
+        write_str("-")?;
+    }
+    let fraction_len = usize::try_from(negative_exponent).unwrap_or(usize::MAX);
+    if let Some(integer_len) = digits.len().checked_sub(fraction_len) {
+        let (integer, fraction) = digits.split_at(integer_len);
+        write_str(if integer.is_empty() { "0" } else { integer })?;
+        if !fraction.is_empty() || pad > 0 {
+            write_str(".")?;
+        }
+        write_str(fraction)?;
+    } else {
+        write_str("0.")?;
+        write_zeros(negative_exponent - digit_count(digits), &mut write_str)?;
+        write_str(digits)?;
+    }
+    write_zeros(pad, &mut write_str)
+}
+
+fn digit_count(digits: &str) -> u32 {
+    // There are at most 20 digits.
+    u32::try_from(digits.len()).unwrap_or(u32::MAX)
+}
+
+fn write_zeros<E, W>(count: u32, write_str: &mut W) -> Result<(), E>
+where
+    W: FnMut(&str) -> Result<(), E>,
+{
+    const ZEROS: &str = "0000000000000000";
+    let mut count = count;
+    while count > 0 {
+        let len = count.min(digit_count(ZEROS));
+        write_str(&ZEROS[..len as usize])?;
+        count -= len;
+    }
+    Ok(())
+}
+
+/// Writes the text produced by `write` with the width, fill, and alignment of the formatter.
+/// Like numbers, the text is aligned right by default.
+///
+/// The text is not buffered. If there is a width, `write` is called twice: once to measure the
+/// text, and once to write it.
+fn pad<W>(f: &mut Formatter<'_>, write: W) -> fmt::Result
+where
+    W: Fn(&mut dyn FnMut(&str) -> fmt::Result) -> fmt::Result,
+{
+    let Some(width) = f.width() else {
+        return write(&mut |s| f.write_str(s));
+    };
+    let mut len = 0_usize;
+    write(&mut |s| {
+        len = len.saturating_add(s.len());
+        Ok(())
+    })?;
+    let padding = width.saturating_sub(len);
+    let (before, after) = match f.align() {
+        Some(Alignment::Left) => (0, padding),
+        Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+        Some(Alignment::Right) | None => (padding, 0),
+    };
+    let fill = f.fill();
+    for _ in 0..before {
+        f.write_char(fill)?;
+    }
+    write(&mut |s| f.write_str(s))?;
+    for _ in 0..after {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+/// Writes the shortest text of the [`Decimal`] number (e.g., `-0.025` or `12`.)
+///
+/// A precision (e.g., `{:.3}`) writes exactly that many digits right of the decimal point,
+/// rounding half to even like `f32` and `f64`. A width, fill, and alignment (e.g., `{:>8}`) pad
+/// the text. The `+` and `0` flags are not supported.
+impl<S> Display for Decimal<S>
+where
+    S: Significand,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(precision) = f.precision() {
+            let negative_exponent = u32::try_from(precision).map_err(|_| fmt::Error)?;
+            let fixed = self.fixed(negative_exponent, Rounding::HalfEven);
+            pad(f, |write_str| fixed.write(write_str))
+        } else {
+            pad(f, |write_str| self.write_minimal(write_str))
+        }
+    }
+}
+
+/// A width, fill, and alignment (e.g., `{:>8}`) pad the text. The `+` and `0` flags are not
+/// supported.
+impl<S> Display for Fixed<S>
+where
+    S: Significand,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        pad(f, |write_str| self.write(write_str))
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl<S> ufmt::uDisplay for Decimal<S>
+where
+    S: Significand,
+{
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        self.write_minimal(|s| f.write_str(s))
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl<S> ufmt::uDisplay for Fixed<S>
+where
+    S: Significand,
+{
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        self.write(|s| f.write_str(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::buffer::Buffer;
+
+    type Text = Buffer<64>;
+
+    fn format(args: fmt::Arguments) -> Text {
+        let mut text = Text::default();
+        text.write_fmt(args).unwrap();
+        text
+    }
+
+    #[test]
+    fn display() {
+        let cases = [
+            (Decimal::new(-25_i32, 3), "-0.025"),
+            (Decimal::new(12, 0), "12"),
+            (Decimal::new(1_500, 3), "1.5"),
+            (Decimal::new(1_200, 2), "12"),
+            (Decimal::new(0, 3), "0"),
+            (Decimal::new(5, 1), "0.5"),
+            (Decimal::new(120, 0), "120"),
+            (Decimal::new(i32::MIN, 4), "-214748.3648"),
+        ];
+        for (decimal, expected) in cases {
+            assert_eq!(format(format_args!("{decimal}")).as_str(), expected);
+        }
+    }
+
+    #[test]
+    fn display_unsigned() {
+        let decimal = Decimal::new(u64::MAX, 20);
+        assert_eq!(
+            format(format_args!("{decimal}")).as_str(),
+            "0.18446744073709551615"
+        );
+    }
+
+    #[test]
+    fn display_precision() {
+        let decimal = Decimal::new(-25_i32, 3);
+        assert_eq!(format(format_args!("{decimal:.5}")).as_str(), "-0.02500");
+        assert_eq!(format(format_args!("{decimal:.2}")).as_str(), "-0.02");
+        assert_eq!(format(format_args!("{decimal:.0}")).as_str(), "0");
+        let decimal = Decimal::new(-5_i32, 3);
+        assert_eq!(format(format_args!("{decimal:.2}")).as_str(), "0.00");
+        let decimal = Decimal::new(12_i32, 0);
+        assert_eq!(format(format_args!("{decimal:.1}")).as_str(), "12.0");
+    }
+
+    #[test]
+    fn display_width() {
+        let decimal = Decimal::new(-25_i32, 3);
+        assert_eq!(format(format_args!("{decimal:8}")).as_str(), "  -0.025");
+        assert_eq!(format(format_args!("{decimal:<8}|")).as_str(), "-0.025  |");
+        assert_eq!(
+            format(format_args!("{decimal:*^9.2}")).as_str(),
+            "**-0.02**"
+        );
+        assert_eq!(format(format_args!("{decimal:3}")).as_str(), "-0.025");
+        let fixed = decimal.fixed(4, Rounding::Truncate);
+        assert_eq!(format(format_args!("{fixed:>8}")).as_str(), " -0.0250");
+    }
+
+    #[test]
+    fn fixed() {
+        let decimal = Decimal::new(12_345_i32, 4);
+        let cases = [
+            (Rounding::Truncate, 3, "1.234"),
+            (Rounding::HalfEven, 3, "1.234"),
+            (Rounding::HalfAwayFromZero, 3, "1.235"),
+            (Rounding::Truncate, 6, "1.234500"),
+            (Rounding::Truncate, 0, "1"),
+        ];
+        for (rounding, negative_exponent, expected) in cases {
+            let fixed = decimal.fixed(negative_exponent, rounding);
+            assert_eq!(format(format_args!("{fixed}")).as_str(), expected);
+        }
+    }
+
+    #[test]
+    fn fixed_does_not_overflow() {
+        let fixed = Decimal::new(i8::MAX, 0).fixed(30, Rounding::Truncate);
+        assert_eq!(
+            format(format_args!("{fixed}")).as_str(),
+            "127.000000000000000000000000000000"
+        );
+    }
+
+    #[test]
+    fn fixed_leading_zeros() {
+        let fixed = Decimal::new(7_u8, 20).fixed(21, Rounding::Truncate);
+        assert_eq!(
+            format(format_args!("{fixed}")).as_str(),
+            "0.000000000000000000070"
+        );
+    }
+
+    #[cfg(feature = "ufmt")]
+    impl<const N: usize> ufmt::uWrite for Buffer<N> {
+        type Error = fmt::Error;
+
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            Write::write_str(self, s)
+        }
+    }
+
+    #[cfg(feature = "ufmt")]
+    #[test]
+    fn u_display() {
+        let mut text = Text::default();
+        let decimal = Decimal::new(-1_500_i32, 3);
+        let fixed = decimal.fixed(2, Rounding::Truncate);
+        ufmt::uwrite!(text, "{} {}", decimal, fixed).unwrap();
+        assert_eq!(text.as_str(), "-1.5 -1.50");
+    }
+}
@@ -0,0 +1,1308 @@
+use crate::arc::{radius_center, ArcError};
+use crate::block::BlockBuilder;
+use crate::cycle::{CannedCycle, Cycle, CycleWords, ReturnMode, Step};
+use crate::decimal::Decimal;
+use crate::expression::ExpressionToken;
+use crate::machine::{zero, Arc, Machine, Plane, Point, AXES};
+use crate::modal::{code, ModalGroup};
+use crate::o_word::OWordKeyword;
+use crate::significand::Significand;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// The motion modes (modal group 1.)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum MotionMode {
+    /// Rapid move (`G0`.)
+    Rapid,
+
+    /// Straight move at the feed rate (`G1`.)
+    Linear,
+
+    /// Clockwise arc at the feed rate (`G2`.)
+    ClockwiseArc,
+
+    /// Counterclockwise arc at the feed rate (`G3`.)
+    CounterclockwiseArc,
+
+    /// Canned cycle (`G73`, `G81`, `G82`, `G83`, `G85`, or `G89`.)
+    CannedCycle(CannedCycle),
+}
+
+/// The distance modes, for coordinates (`G90` and `G91`) and for arc centers (`G90.1` and
+/// `G91.1`.)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum DistanceMode {
+    /// Values are absolute coordinates.
+    Absolute,
+
+    /// Values are relative to the current position (or, for arc centers, to the start of the
+    /// arc.)
+    Incremental,
+}
+
+/// The units of lengths (`G20` and `G21`.)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum Units {
+    /// Inches (`G20`.)
+    Inches,
+
+    /// Millimeters (`G21`.)
+    #[default]
+    Millimeters,
+}
+
+/// The feed rate modes (`G93`, `G94`, and `G95`.)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum FeedRateMode {
+    /// The feed rate is the inverse of the duration of each move, in minutes (`G93`.)
+    InverseTime,
+
+    /// The feed rate is a length per minute (`G94`.)
+    #[default]
+    UnitsPerMinute,
+
+    /// The feed rate is a length per revolution of the spindle (`G95`.)
+    UnitsPerRevolution,
+}
+
+/// The directions of the spindle (`M3`, `M4`, and `M5`.)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum SpindleDirection {
+    /// Clockwise (`M3`.)
+    Clockwise,
+
+    /// Counterclockwise (`M4`.)
+    Counterclockwise,
+
+    /// Stopped (`M5`.)
+    #[default]
+    Stopped,
+}
+
+/// The number of coordinate systems (`G54` to `G59`, `G59.1`, `G59.2`, and `G59.3`.)
+pub const COORDINATE_SYSTEMS: usize = 9;
+
+/// The effective state of the machine, as maintained by the [`Interpreter`].
+///
+/// All lengths are in millimeters, whatever the [`Units`] of the program.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct MachineState<S>
+where
+    S: Significand,
+{
+    motion: Option<MotionMode>,
+    distance: DistanceMode,
+    arc_distance: DistanceMode,
+    units: Units,
+    plane: Plane,
+    feed_rate_mode: FeedRateMode,
+    coordinate_system: usize,
+    work_offsets: [Point<S>; COORDINATE_SYSTEMS],
+    axis_offset: Point<S>,
+    position: Point<S>,
+    feed_rate: Decimal<S>,
+    spindle_speed: Decimal<S>,
+    spindle: SpindleDirection,
+    mist: bool,
+    flood: bool,
+    tool: Option<Decimal<S>>,
+    return_mode: ReturnMode,
+    cycle: CycleWords<S>,
+}
+
+impl<S> Default for MachineState<S>
+where
+    S: Significand,
+{
+    /// Returns the state at power on: `G0 G17 G21 G40 G49 G54 G90 G91.1 G94 G98 M5 M9`, at the origin,
+    /// with a feed rate and a spindle speed of zero.
+    fn default() -> Self {
+        Self {
+            motion: Some(MotionMode::Rapid),
+            distance: DistanceMode::Absolute,
+            arc_distance: DistanceMode::Incremental,
+            units: Units::default(),
+            plane: Plane::default(),
+            feed_rate_mode: FeedRateMode::default(),
+            coordinate_system: 0,
+            work_offsets: [Point::default(); COORDINATE_SYSTEMS],
+            axis_offset: Point::default(),
+            position: Point::default(),
+            feed_rate: zero(),
+            spindle_speed: zero(),
+            spindle: SpindleDirection::default(),
+            mist: false,
+            flood: false,
+            tool: None,
+            return_mode: ReturnMode::default(),
+            cycle: CycleWords::default(),
+        }
+    }
+}
+
+impl<S> MachineState<S>
+where
+    S: Significand,
+{
+    /// Returns the motion mode, or `None` after `G80`.
+    #[must_use]
+    pub const fn motion(&self) -> Option<MotionMode> {
+        self.motion
+    }
+
+    /// Returns the distance mode of coordinates (`G90` or `G91`.)
+    #[must_use]
+    pub const fn distance(&self) -> DistanceMode {
+        self.distance
+    }
+
+    /// Returns the distance mode of arc centers (`G90.1` or `G91.1`.)
+    #[must_use]
+    pub const fn arc_distance(&self) -> DistanceMode {
+        self.arc_distance
+    }
+
+    /// Returns the units of the program.
+    #[must_use]
+    pub const fn units(&self) -> Units {
+        self.units
+    }
+
+    /// Returns the plane of arcs.
+    #[must_use]
+    pub const fn plane(&self) -> Plane {
+        self.plane
+    }
+
+    /// Returns the feed rate mode.
+    #[must_use]
+    pub const fn feed_rate_mode(&self) -> FeedRateMode {
+        self.feed_rate_mode
+    }
+
+    /// Returns the index of the active coordinate system, from 0 for `G54` to 8 for `G59.3`.
+    #[must_use]
+    pub const fn coordinate_system(&self) -> usize {
+        self.coordinate_system
+    }
+
+    /// Returns the offset of a coordinate system, or `None` if the index is out of range.
+    #[must_use]
+    pub fn work_offset(&self, coordinate_system: usize) -> Option<Point<S>> {
+        self.work_offsets.get(coordinate_system).copied()
+    }
+
+    /// Returns the offset set by `G92`.
+    #[must_use]
+    pub const fn axis_offset(&self) -> Point<S> {
+        self.axis_offset
+    }
+
+    /// Returns the total offset of program coordinates (i.e., the offset of the active coordinate
+    /// system plus the offset set by `G92`), or `None` if overflow occurred.
+    #[must_use]
+    pub fn offset(&self) -> Option<Point<S>> {
+        self.work_offsets[self.coordinate_system].checked_add(self.axis_offset)
+    }
+
+    /// Returns the current position, in machine coordinates.
+    #[must_use]
+    pub const fn position(&self) -> Point<S> {
+        self.position
+    }
+
+    /// Returns the current position, in program coordinates (but still in millimeters), or `None`
+    /// if overflow occurred.
+    #[must_use]
+    pub fn program_position(&self) -> Option<Point<S>> {
+        self.position.checked_sub(self.offset()?)
+    }
+
+    /// Returns the feed rate. See [`Machine::linear`] for its units.
+    #[must_use]
+    pub const fn feed_rate(&self) -> Decimal<S> {
+        self.feed_rate
+    }
+
+    /// Returns the spindle speed, in revolutions per minute.
+    #[must_use]
+    pub const fn spindle_speed(&self) -> Decimal<S> {
+        self.spindle_speed
+    }
+
+    /// Returns the direction of the spindle.
+    #[must_use]
+    pub const fn spindle(&self) -> SpindleDirection {
+        self.spindle
+    }
+
+    /// Returns `true` if mist coolant is on (`M7`.)
+    #[must_use]
+    pub const fn mist(&self) -> bool {
+        self.mist
+    }
+
+    /// Returns `true` if flood coolant is on (`M8`.)
+    #[must_use]
+    pub const fn flood(&self) -> bool {
+        self.flood
+    }
+
+    /// Returns the selected tool (the last `T` word), if any.
+    #[must_use]
+    pub const fn tool(&self) -> Option<Decimal<S>> {
+        self.tool
+    }
+
+    /// Returns the return mode of canned cycles (`G98` or `G99`.)
+    #[must_use]
+    pub const fn return_mode(&self) -> ReturnMode {
+        self.return_mode
+    }
+
+    /// Converts a length in the units of the program to millimeters.
+    fn to_millimeters<E>(&self, length: Decimal<S>) -> Result<Decimal<S>, InterpreterError<E>> {
+        match self.units {
+            Units::Millimeters => Ok(length),
+            Units::Inches => decimal(254, 1)
+                .and_then(|millimeters| length.checked_mul(millimeters))
+                .ok_or(InterpreterError::Overflow),
+        }
+    }
+}
+
+/// The error type for the [`Interpreter`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum InterpreterError<E> {
+    /// The [`Machine`] produced an error.
+    MachineError(E),
+
+    /// The block has two codes of the same [`ModalGroup`] (e.g., `G0 G1`.)
+    ModalGroupConflict(ModalGroup),
+
+    /// The block has two words with the same address (e.g., `X1 X2`.) The address is in upper
+    /// case.
+    DuplicateWord(char),
+
+    /// The block is missing a required word (e.g., the `P` word of `G4`.) The address is in upper
+    /// case.
+    MissingWord(char),
+
+    /// The value of a word is out of range (e.g., a peck depth `Q` that is not positive, or a
+    /// retract level `R` below the bottom of the hole.) The address is in upper case.
+    InvalidWord(char),
+
+    /// The block has axis words, but there is no motion mode (after `G80`.)
+    NoMotionMode,
+
+    /// The block has an arc without a center offset in the plane of the arc, or with a radius
+    /// (`R` word) that is zero or too small to reach its end.
+    InvalidArc,
+
+    /// A computation exceeded the bounds of the [`Significand`] numeric type.
+    Overflow,
+
+    /// The block has a code, an address, or a feature that the [`Interpreter`] does not support
+    /// (e.g., `G41`, a parameter, or an expression.)
+    Unsupported,
+}
+
+impl<E> Eq for InterpreterError<E> where E: Eq {}
+
+/// A [`BlockBuilder`] that interprets each block and sends the resolved motion to a [`Machine`].
+///
+/// The [`Interpreter`] collects the words of each block. At the end of the block, it updates the
+/// [`MachineState`] and calls the [`Machine`] in the order of execution of RS-274/NGC (e.g., the
+/// feed rate and the spindle are set before the motion, whatever their order in the block.) It
+/// checks the whole block first, so a block that produces an error (other than an error of the
+/// [`Machine`]) changes neither the state nor the machine. It does not evaluate parameters or
+/// expressions.
+///
+///     use gcode::{Arc, BlockParser, Decimal, Interpreter, Machine, Point};
+///
+///     #[derive(Default)]
+///     struct Moves(usize);
+///
+///     impl Machine for Moves {
+///         type Error = ();
+///         type Significand = i32;
+///
+///         fn rapid(&mut self, end: Point<i32>) -> Result<(), ()> {
+///             self.0 += 1;
+///             Ok(())
+///         }
+///
+///         fn linear(&mut self, end: Point<i32>, feed_rate: Decimal<i32>) -> Result<(), ()> {
+///             self.0 += 1;
+///             Ok(())
+///         }
+///
+///         fn arc(&mut self, arc: Arc<i32>, feed_rate: Decimal<i32>) -> Result<(), ()> {
+///             self.0 += 1;
+///             Ok(())
+///         }
+///     }
+///
+///     let mut parser = BlockParser::<i32>::default();
+///     let mut interpreter = Interpreter::new(Moves::default());
+///     parser.try_feed_str("G20 G91 G1 F10 X1\nY2\n", &mut interpreter).unwrap();
+///     assert_eq!(interpreter.machine().0, 2);
+///     let position = interpreter.state().position();
+///     assert_eq!(position.x(), Decimal::new(254, 1));
+///     assert_eq!(position.y(), Decimal::new(508, 1));
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Interpreter<M>
+where
+    M: Machine,
+{
+    machine: M,
+    state: MachineState<M::Significand>,
+    block: Words<M::Significand>,
+}
+
+impl<M> Interpreter<M>
+where
+    M: Machine,
+{
+    /// Creates a new [`Interpreter`] that sends motion to `machine`, starting from the default
+    /// [`MachineState`].
+    pub fn new(machine: M) -> Self {
+        Self {
+            machine,
+            state: MachineState::default(),
+            block: Words::default(),
+        }
+    }
+
+    /// Returns a reference to the machine.
+    pub const fn machine(&self) -> &M {
+        &self.machine
+    }
+
+    /// Returns a mutable reference to the machine.
+    pub const fn machine_mut(&mut self) -> &mut M {
+        &mut self.machine
+    }
+
+    /// Returns the machine.
+    pub fn into_machine(self) -> M {
+        self.machine
+    }
+
+    /// Returns the state after the last complete block.
+    pub const fn state(&self) -> &MachineState<M::Significand> {
+        &self.state
+    }
+
+    /// Sets the offset of a coordinate system, from 0 for `G54` to 8 for `G59.3` (e.g., as
+    /// restored from non-volatile memory.) Does nothing if the index is out of range.
+    pub fn set_work_offset(&mut self, coordinate_system: usize, offset: Point<M::Significand>) {
+        if let Some(work_offset) = self.state.work_offsets.get_mut(coordinate_system) {
+            *work_offset = offset;
+        }
+    }
+
+    /// Sets the current position, in machine coordinates (e.g., after homing.)
+    pub const fn set_position(&mut self, position: Point<M::Significand>) {
+        self.state.position = position;
+    }
+
+    /// Resolves the whole block on a copy of the state before it calls the machine, so that an
+    /// invalid block changes neither the state nor the machine.
+    fn execute(&mut self, words: &Words<M::Significand>) -> Result<(), InterpreterError<M::Error>> {
+        let mut state = self.state.clone();
+        state.set_modes(words)?;
+        state.set_offsets(words)?;
+        let dwell = match words.code(ModalGroup::NonModal).and_then(code) {
+            Some((4, 0)) => Some(words.word('P').ok_or(InterpreterError::MissingWord('P'))?),
+            _ => None,
+        };
+        let motion = state.resolve_motion(words)?;
+        if let Some(tool) = words.word('T') {
+            self.machine
+                .select_tool(tool)
+                .map_err(InterpreterError::MachineError)?;
+        }
+        for number in [
+            words.code(ModalGroup::ToolChange),
+            words.code(ModalGroup::Override),
+        ]
+        .into_iter()
+        .chain(words.m_codes)
+        .flatten()
+        {
+            self.machine
+                .m_code(number)
+                .map_err(InterpreterError::MachineError)?;
+        }
+        self.state = state;
+        self.machine
+            .modal_state(&self.state)
+            .map_err(InterpreterError::MachineError)?;
+        if let Some(seconds) = dwell {
+            self.step(Step::Dwell(seconds))?;
+        }
+        match motion {
+            None => {}
+            Some(Motion::Step(step)) => self.step(step)?,
+            Some(Motion::Arc(arc, feed_rate)) => {
+                self.machine
+                    .arc(arc, feed_rate)
+                    .map_err(InterpreterError::MachineError)?;
+                self.state.position = arc.end();
+            }
+            Some(Motion::Cycle(cycle)) => cycle.run(self.state.position, |step| self.step(step))?,
+        }
+        if let Some(number) = words.code(ModalGroup::Stopping) {
+            self.machine
+                .m_code(number)
+                .map_err(InterpreterError::MachineError)?;
+        }
+        Ok(())
+    }
+
+    /// Sends a step to the machine, and updates the position when it succeeds.
+    fn step(&mut self, step: Step<M::Significand>) -> Result<(), InterpreterError<M::Error>> {
+        let end = match step {
+            Step::Rapid(end) => self.machine.rapid(end).map(|()| end),
+            Step::Linear(end, feed_rate) => self.machine.linear(end, feed_rate).map(|()| end),
+            Step::Dwell(seconds) => self.machine.dwell(seconds).map(|()| self.state.position),
+        };
+        self.state.position = end.map_err(InterpreterError::MachineError)?;
+        Ok(())
+    }
+}
+
+/// The motion of a block, resolved to machine coordinates.
+enum Motion<S>
+where
+    S: Significand,
+{
+    Step(Step<S>),
+    Arc(Arc<S>, Decimal<S>),
+    Cycle(Cycle<S>),
+}
+
+impl<S> MachineState<S>
+where
+    S: Significand,
+{
+    /// Applies the modal codes, the feed rate, the spindle speed, and the tool of the block.
+    fn set_modes<E>(&mut self, words: &Words<S>) -> Result<(), InterpreterError<E>> {
+        for group in ModalGroup::ALL {
+            let Some(number) = words.code(group) else {
+                continue;
+            };
+            let code = code(number).ok_or(InterpreterError::Unsupported)?;
+            match (group, code) {
+                (ModalGroup::Units, (20, 0)) => self.units = Units::Inches,
+                (ModalGroup::Units, _) => self.units = Units::Millimeters,
+                (ModalGroup::FeedRateMode, (93, 0)) => {
+                    self.feed_rate_mode = FeedRateMode::InverseTime;
+                }
+                (ModalGroup::FeedRateMode, (94, 0)) => {
+                    self.feed_rate_mode = FeedRateMode::UnitsPerMinute;
+                }
+                (ModalGroup::FeedRateMode, _) => {
+                    self.feed_rate_mode = FeedRateMode::UnitsPerRevolution;
+                }
+                (ModalGroup::Spindle, (3, 0)) => self.spindle = SpindleDirection::Clockwise,
+                (ModalGroup::Spindle, (4, 0)) => {
+                    self.spindle = SpindleDirection::Counterclockwise;
+                }
+                (ModalGroup::Spindle, _) => self.spindle = SpindleDirection::Stopped,
+                (ModalGroup::Coolant, (7, 0)) => self.mist = true,
+                (ModalGroup::Coolant, (8, 0)) => self.flood = true,
+                (ModalGroup::Coolant, _) => (self.mist, self.flood) = (false, false),
+                (ModalGroup::Plane, (17, 0)) => self.plane = Plane::XY,
+                (ModalGroup::Plane, (18, 0)) => self.plane = Plane::ZX,
+                (ModalGroup::Plane, (19, 0)) => self.plane = Plane::YZ,
+                (ModalGroup::Distance, (90, _)) => self.distance = DistanceMode::Absolute,
+                (ModalGroup::Distance, _) => self.distance = DistanceMode::Incremental,
+                (ModalGroup::ArcDistance, (90, _)) => self.arc_distance = DistanceMode::Absolute,
+                (ModalGroup::ArcDistance, _) => self.arc_distance = DistanceMode::Incremental,
+                (ModalGroup::CoordinateSystem, (integer, tenths)) => {
+                    self.coordinate_system = usize::from(integer - 54 + tenths);
+                }
+                (ModalGroup::Motion, (0, 0)) => self.motion = Some(MotionMode::Rapid),
+                (ModalGroup::Motion, (1, 0)) => self.motion = Some(MotionMode::Linear),
+                (ModalGroup::Motion, (2, 0)) => self.motion = Some(MotionMode::ClockwiseArc),
+                (ModalGroup::Motion, (3, 0)) => {
+                    self.motion = Some(MotionMode::CounterclockwiseArc);
+                }
+                (ModalGroup::Motion, (80, 0)) => self.motion = None,
+                (ModalGroup::Motion, (73 | 81..=83 | 85 | 89, 0)) => {
+                    let cycle = match code.0 {
+                        73 => CannedCycle::ChipBreak,
+                        81 => CannedCycle::Drill,
+                        82 => CannedCycle::DrillDwell,
+                        83 => CannedCycle::PeckDrill,
+                        85 => CannedCycle::Bore,
+                        _ => CannedCycle::BoreDwell,
+                    };
+                    self.motion = Some(MotionMode::CannedCycle(cycle));
+                }
+                (ModalGroup::ReturnMode, (98, 0)) => self.return_mode = ReturnMode::InitialLevel,
+                (ModalGroup::ReturnMode, _) => self.return_mode = ReturnMode::RetractLevel,
+                // These codes are handled elsewhere, or have no effect on the motion.
+                (ModalGroup::NonModal, (4 | 92, 0) | (92, 1))
+                | (ModalGroup::CutterCompensation, (40, 0))
+                | (ModalGroup::ToolLengthOffset, (49, 0))
+                | (ModalGroup::SpindleSpeedMode, (97, 0))
+                | (
+                    ModalGroup::PathControl
+                    | ModalGroup::ToolChange
+                    | ModalGroup::Override
+                    | ModalGroup::Stopping,
+                    _,
+                ) => {}
+                _ => return Err(InterpreterError::Unsupported),
+            }
+        }
+        // Canned cycles forget their words when the motion mode changes.
+        if !matches!(self.motion, Some(MotionMode::CannedCycle(_))) {
+            self.cycle = CycleWords::default();
+        }
+        // The units of the block apply to its feed rate.
+        if let Some(feed_rate) = words.word('F') {
+            self.feed_rate = if FeedRateMode::InverseTime == self.feed_rate_mode {
+                feed_rate
+            } else {
+                self.to_millimeters(feed_rate)?
+            };
+        }
+        if let Some(spindle_speed) = words.word('S') {
+            self.spindle_speed = spindle_speed;
+        }
+        if let Some(tool) = words.word('T') {
+            self.tool = Some(tool);
+        }
+        Ok(())
+    }
+
+    /// Applies `G92` and `G92.1`.
+    fn set_offsets<E>(&mut self, words: &Words<S>) -> Result<(), InterpreterError<E>> {
+        match words.code(ModalGroup::NonModal).and_then(code) {
+            Some((92, 0)) => {
+                // The axis offset is chosen so that the current position has the coordinates of
+                // the axis words.
+                let work_offset = self.work_offsets[self.coordinate_system];
+                for axis in 0..AXES {
+                    if let Some(value) = words.axis(axis) {
+                        let value = self.to_millimeters(value)?;
+                        let offset = self
+                            .position
+                            .axis(axis)
+                            .checked_sub(work_offset.axis(axis))
+                            .and_then(|offset| offset.checked_sub(value))
+                            .ok_or(InterpreterError::Overflow)?;
+                        self.axis_offset.set_axis(axis, offset);
+                    }
+                }
+            }
+            Some((92, 1)) => self.axis_offset = Point::default(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Returns the motion to the axis words of the block, if any, in the motion mode.
+    fn resolve_motion<E>(
+        &mut self,
+        words: &Words<S>,
+    ) -> Result<Option<Motion<S>>, InterpreterError<E>> {
+        let uses_axes = words.code(ModalGroup::NonModal).and_then(code) == Some((92, 0));
+        if uses_axes || (0..AXES).all(|axis| words.axis(axis).is_none()) {
+            return Ok(None);
+        }
+        let motion = self.motion.ok_or(InterpreterError::NoMotionMode)?;
+        let start = self.position;
+        let feed_rate = self.feed_rate;
+        let motion = match motion {
+            MotionMode::Rapid => Motion::Step(Step::Rapid(self.target(words)?)),
+            MotionMode::Linear => Motion::Step(Step::Linear(self.target(words)?, feed_rate)),
+            MotionMode::ClockwiseArc | MotionMode::CounterclockwiseArc => {
+                let end = self.target(words)?;
+                let clockwise = MotionMode::ClockwiseArc == motion;
+                let center = self.center(start, end, clockwise, words)?;
+                Motion::Arc(
+                    Arc::new(start, end, center, self.plane, clockwise),
+                    feed_rate,
+                )
+            }
+            MotionMode::CannedCycle(cycle) => Motion::Cycle(self.canned_cycle(cycle, words)?),
+        };
+        Ok(Some(motion))
+    }
+
+    /// Returns a canned cycle at the axis words of the block, repeated as many times as the `L`
+    /// word.
+    ///
+    /// With `G91`, the `R` word is relative to the initial position, the drilling axis word is
+    /// relative to the retract level, and each repetition moves by the other axis words.
+    fn canned_cycle<E>(
+        &mut self,
+        kind: CannedCycle,
+        words: &Words<S>,
+    ) -> Result<Cycle<S>, InterpreterError<E>> {
+        if FeedRateMode::InverseTime == self.feed_rate_mode {
+            return Err(InterpreterError::Unsupported);
+        }
+        let (first, second, normal) = self.plane.axes();
+        let offset = self.offset().ok_or(InterpreterError::Overflow)?;
+        let level = self.position.axis(normal);
+        // The words are remembered only once the whole cycle is valid.
+        let mut remembered = self.cycle;
+        let initial = *remembered.initial.get_or_insert(level);
+        let sticky = |word: Option<Decimal<S>>, value: &mut Option<_>, address| {
+            *value = word.or(*value);
+            value.ok_or(InterpreterError::MissingWord(address))
+        };
+        let retract = sticky(words.word('R'), &mut remembered.retract, 'R');
+        let bottom = sticky(
+            words.axis(normal),
+            &mut remembered.bottom,
+            AXIS_ADDRESSES[normal],
+        );
+        let peck = sticky(words.word('Q'), &mut remembered.peck, 'Q');
+        let dwell = sticky(words.word('P'), &mut remembered.dwell, 'P');
+        let retract = self.to_millimeters(retract?)?;
+        let bottom = self.to_millimeters(bottom?)?;
+        let base = match self.distance {
+            DistanceMode::Absolute => offset.axis(normal),
+            DistanceMode::Incremental => level,
+        };
+        let retract = base
+            .checked_add(retract)
+            .ok_or(InterpreterError::Overflow)?;
+        let base = match self.distance {
+            DistanceMode::Absolute => offset.axis(normal),
+            DistanceMode::Incremental => retract,
+        };
+        let bottom = base.checked_add(bottom).ok_or(InterpreterError::Overflow)?;
+        if bottom >= retract {
+            return Err(InterpreterError::InvalidWord('R'));
+        }
+        let peck = if kind.pecks() {
+            let peck = self.to_millimeters(peck?)?;
+            if peck <= zero() {
+                return Err(InterpreterError::InvalidWord('Q'));
+            }
+            peck
+        } else {
+            zero()
+        };
+        let dwell = if kind.dwells() {
+            let dwell = dwell?;
+            if dwell < zero() {
+                return Err(InterpreterError::InvalidWord('P'));
+            }
+            dwell
+        } else {
+            zero()
+        };
+        let repeat = match words.word('L').map(code) {
+            None => 1,
+            Some(Some((repeat @ 1.., 0))) => repeat,
+            Some(_) => return Err(InterpreterError::InvalidWord('L')),
+        };
+        // In absolute mode, each repetition drills the same hole.
+        let mut hole = [first, second].map(|axis| self.position.axis(axis));
+        let mut spacing = [zero(), zero()];
+        for ((axis, hole), spacing) in [first, second].into_iter().zip(&mut hole).zip(&mut spacing)
+        {
+            if let Some(value) = words.axis(axis) {
+                let value = self.to_millimeters(value)?;
+                let base = match self.distance {
+                    DistanceMode::Absolute => offset.axis(axis),
+                    DistanceMode::Incremental => {
+                        *spacing = value;
+                        *hole
+                    }
+                };
+                *hole = base.checked_add(value).ok_or(InterpreterError::Overflow)?;
+            }
+        }
+        let cycle = Cycle {
+            kind,
+            axes: (first, second, normal),
+            hole: hole.into(),
+            spacing: spacing.into(),
+            repeat,
+            clear: match self.return_mode {
+                ReturnMode::InitialLevel => initial.max(retract),
+                ReturnMode::RetractLevel => retract,
+            },
+            retract,
+            bottom,
+            peck,
+            clearance: decimal(254, 3).ok_or(InterpreterError::Overflow)?,
+            dwell,
+            feed_rate: self.feed_rate,
+        };
+        // Check every step for overflow before the first one is sent to the machine.
+        cycle.run::<E, _>(self.position, |_| Ok(()))?;
+        self.cycle = remembered;
+        Ok(cycle)
+    }
+
+    /// Returns the end of the move, in machine coordinates.
+    fn target<E>(&self, words: &Words<S>) -> Result<Point<S>, InterpreterError<E>> {
+        let offset = self.offset().ok_or(InterpreterError::Overflow)?;
+        let mut target = self.position;
+        for axis in 0..AXES {
+            if let Some(value) = words.axis(axis) {
+                let value = self.to_millimeters(value)?;
+                let base = match self.distance {
+                    DistanceMode::Absolute => offset.axis(axis),
+                    DistanceMode::Incremental => self.position.axis(axis),
+                };
+                let value = base.checked_add(value).ok_or(InterpreterError::Overflow)?;
+                target.set_axis(axis, value);
+            }
+        }
+        Ok(target)
+    }
+
+    /// Returns the center of an arc from the `R` word or the `I`, `J`, and `K` words of the block.
+    fn center<E>(
+        &self,
+        start: Point<S>,
+        end: Point<S>,
+        clockwise: bool,
+        words: &Words<S>,
+    ) -> Result<Point<S>, InterpreterError<E>> {
+        if let Some(radius) = words.word('R') {
+            let radius = self.to_millimeters(radius)?;
+            return radius_center(start, end, radius, self.plane, clockwise).map_err(|error| {
+                if ArcError::Overflow == error {
+                    InterpreterError::Overflow
+                } else {
+                    InterpreterError::InvalidArc
+                }
+            });
+        }
+        let (first, second, _) = self.plane.axes();
+        let offsets = [first, second].map(|axis| words.center_offset(axis));
+        if offsets.iter().all(Option::is_none) {
+            return Err(InterpreterError::InvalidArc);
+        }
+        let base = match self.arc_distance {
+            DistanceMode::Absolute => self.offset().ok_or(InterpreterError::Overflow)?,
+            DistanceMode::Incremental => start,
+        };
+        let mut center = start;
+        for (axis, offset) in [first, second].into_iter().zip(offsets) {
+            let offset = self.to_millimeters(offset.unwrap_or_else(zero))?;
+            let value = base
+                .axis(axis)
+                .checked_add(offset)
+                .ok_or(InterpreterError::Overflow)?;
+            center.set_axis(axis, value);
+        }
+        Ok(center)
+    }
+}
+
+impl<M> BlockBuilder for Interpreter<M>
+where
+    M: Machine,
+{
+    type Error = InterpreterError<M::Error>;
+    type Significand = M::Significand;
+
+    fn program_start(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn sequence_number(
+        &mut self,
+        alignment: bool,
+        number: Decimal<Self::SequenceNumber>,
+    ) -> Result<(), Self::Error> {
+        let _ = (alignment, number);
+        Ok(())
+    }
+
+    fn g_code(&mut self, number: Decimal<Self::Significand>) -> Result<(), Self::Error> {
+        let group = ModalGroup::of_g_code(number).ok_or(InterpreterError::Unsupported)?;
+        self.block.insert_code(group, number)
+    }
+
+    fn m_code(&mut self, number: Decimal<Self::Significand>) -> Result<(), Self::Error> {
+        if let Some(group) = ModalGroup::of_m_code(number) {
+            self.block.insert_code(group, number)
+        } else {
+            let slot = self
+                .block
+                .m_codes
+                .iter_mut()
+                .find(|slot| slot.is_none())
+                .ok_or(InterpreterError::Unsupported)?;
+            *slot = Some(number);
+            Ok(())
+        }
+    }
+
+    fn data(
+        &mut self,
+        address: char,
+        index: Option<Self::Index>,
+        number: Decimal<Self::Significand>,
+    ) -> Result<(), Self::Error> {
+        if index.is_some() {
+            return Err(InterpreterError::Unsupported);
+        }
+        self.block.insert_word(address, number)
+    }
+
+    fn parameter_word(
+        &mut self,
+        address: char,
+        index: Option<Self::Index>,
+        parameter: Self::Index,
+    ) -> Result<(), Self::Error> {
+        let _ = (address, index, parameter);
+        Err(InterpreterError::Unsupported)
+    }
+
+    fn parameter_assignment(
+        &mut self,
+        parameter: Self::Index,
+        number: Decimal<Self::Significand>,
+    ) -> Result<(), Self::Error> {
+        let _ = (parameter, number);
+        Err(InterpreterError::Unsupported)
+    }
+
+    fn expression(
+        &mut self,
+        token: ExpressionToken<Self::Significand, Self::Index>,
+    ) -> Result<(), Self::Error> {
+        let _ = token;
+        Err(InterpreterError::Unsupported)
+    }
+
+    fn expression_word(
+        &mut self,
+        address: char,
+        index: Option<Self::Index>,
+    ) -> Result<(), Self::Error> {
+        let _ = (address, index);
+        Err(InterpreterError::Unsupported)
+    }
+
+    fn expression_assignment(&mut self, parameter: Self::Index) -> Result<(), Self::Error> {
+        let _ = parameter;
+        Err(InterpreterError::Unsupported)
+    }
+
+    fn o_word(&mut self, number: Self::Index, keyword: OWordKeyword) -> Result<(), Self::Error> {
+        let _ = (number, keyword);
+        Err(InterpreterError::Unsupported)
+    }
+
+    fn o_word_argument(&mut self) -> Result<(), Self::Error> {
+        Err(InterpreterError::Unsupported)
+    }
+
+    fn checksum(&mut self, checksum: u8, valid: bool) -> Result<(), Self::Error> {
+        let _ = (checksum, valid);
+        Ok(())
+    }
+
+    fn end_block(&mut self) -> Result<(), Self::Error> {
+        let words = core::mem::take(&mut self.block);
+        self.execute(&words)
+    }
+
+    fn abort_block(&mut self) -> Result<(), Self::Error> {
+        self.block = Words::default();
+        Ok(())
+    }
+}
+
+/// The addresses of the words that the [`Interpreter`] supports.
+const ADDRESSES: &str = "FIJKLPQRSTXYZ";
+
+/// The addresses of the axis words.
+const AXIS_ADDRESSES: [char; AXES] = ['X', 'Y', 'Z'];
+
+/// The maximum number of M codes without a modal group in a block.
+const M_CODES: usize = 4;
+
+/// The codes and words of a block.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+struct Words<S>
+where
+    S: Significand,
+{
+    codes: [Option<Decimal<S>>; ModalGroup::ALL.len()],
+    m_codes: [Option<Decimal<S>>; M_CODES],
+    values: [Option<Decimal<S>>; ADDRESSES.len()],
+}
+
+impl<S> Default for Words<S>
+where
+    S: Significand,
+{
+    fn default() -> Self {
+        Self {
+            codes: [None; ModalGroup::ALL.len()],
+            m_codes: [None; M_CODES],
+            values: [None; ADDRESSES.len()],
+        }
+    }
+}
+
+impl<S> Words<S>
+where
+    S: Significand,
+{
+    const fn insert_code<E>(
+        &mut self,
+        group: ModalGroup,
+        number: Decimal<S>,
+    ) -> Result<(), InterpreterError<E>> {
+        let slot = &mut self.codes[group.index()];
+        if slot.is_some() {
+            return Err(InterpreterError::ModalGroupConflict(group));
+        }
+        *slot = Some(number);
+        Ok(())
+    }
+
+    fn insert_word<E>(
+        &mut self,
+        address: char,
+        number: Decimal<S>,
+    ) -> Result<(), InterpreterError<E>> {
+        let address = address.to_ascii_uppercase();
+        let index = ADDRESSES
+            .find(address)
+            .ok_or(InterpreterError::Unsupported)?;
+        let slot = &mut self.values[index];
+        if slot.is_some() {
+            return Err(InterpreterError::DuplicateWord(address));
+        }
+        *slot = Some(number);
+        Ok(())
+    }
+
+    const fn code(&self, group: ModalGroup) -> Option<Decimal<S>> {
+        self.codes[group.index()]
+    }
+
+    /// Returns the value of a word. The address must be one of [`ADDRESSES`], in upper case.
+    fn word(&self, address: char) -> Option<Decimal<S>> {
+        ADDRESSES.find(address).and_then(|index| self.values[index])
+    }
+
+    /// Returns the value of the `X`, `Y`, or `Z` word.
+    fn axis(&self, axis: usize) -> Option<Decimal<S>> {
+        self.word(AXIS_ADDRESSES[axis])
+    }
+
+    /// Returns the value of the `I`, `J`, or `K` word.
+    fn center_offset(&self, axis: usize) -> Option<Decimal<S>> {
+        self.word(char::from(b"IJK"[axis]))
+    }
+}
+
+/// Returns a [`Decimal`] number, or `None` if the significand does not fit.
+fn decimal<S>(significand: u32, negative_exponent: u32) -> Option<Decimal<S>>
+where
+    S: Significand,
+{
+    let significand = S::default().checked_add_unsigned(significand)?;
+    Some(Decimal::new(significand, negative_exponent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::block::BlockParser;
+    use crate::config::Config;
+    use crate::testing::{error, interpret, moves, point, Move};
+
+    extern crate std;
+    use std::vec::Vec;
+
+    #[test]
+    fn machine_state_default() {
+        let state = MachineState::<i32>::default();
+        assert_eq!(state.motion(), Some(MotionMode::Rapid));
+        assert_eq!(state.distance(), DistanceMode::Absolute);
+        assert_eq!(state.arc_distance(), DistanceMode::Incremental);
+        assert_eq!(state.units(), Units::Millimeters);
+        assert_eq!(state.plane(), Plane::XY);
+        assert_eq!(state.feed_rate_mode(), FeedRateMode::UnitsPerMinute);
+        assert_eq!(state.coordinate_system(), 0);
+        assert_eq!(state.position(), Point::default());
+        assert_eq!(state.spindle(), SpindleDirection::Stopped);
+        assert!(!state.mist());
+        assert!(!state.flood());
+        assert_eq!(state.tool(), None);
+    }
+
+    #[test]
+    fn interpreter_modes() {
+        let interpreter = interpret(
+            Vec::new(),
+            "G18 G20 G91 G93 G55 G1 F2 S1200 T3 M4 M7\nG90.1 M8\n",
+        );
+        let state = interpreter.state();
+        assert_eq!(state.motion(), Some(MotionMode::Linear));
+        assert_eq!(state.distance(), DistanceMode::Incremental);
+        assert_eq!(state.arc_distance(), DistanceMode::Absolute);
+        assert_eq!(state.units(), Units::Inches);
+        assert_eq!(state.plane(), Plane::ZX);
+        assert_eq!(state.feed_rate_mode(), FeedRateMode::InverseTime);
+        assert_eq!(state.coordinate_system(), 1);
+        assert_eq!(state.feed_rate(), Decimal::new(2, 0));
+        assert_eq!(state.spindle_speed(), Decimal::new(1200, 0));
+        assert_eq!(state.spindle(), SpindleDirection::Counterclockwise);
+        assert_eq!(state.tool(), Some(Decimal::new(3, 0)));
+        assert!(state.mist());
+        assert!(state.flood());
+        let interpreter = interpret(Vec::new(), "G59.3 M7 M3\nM9 G80 M5\n");
+        let state = interpreter.state();
+        assert_eq!(state.coordinate_system(), 8);
+        assert_eq!(state.motion(), None);
+        assert_eq!(state.spindle(), SpindleDirection::Stopped);
+        assert!(!state.mist());
+    }
+
+    #[test]
+    fn interpreter_moves() {
+        assert_eq!(
+            moves("G0 X1 Y2\nG1 Z-1 F100\nX3\n"),
+            [
+                Move::Rapid(point(1, 2, 0)),
+                Move::Linear(point(1, 2, -1), Decimal::new(100, 0)),
+                Move::Linear(point(3, 2, -1), Decimal::new(100, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn interpreter_incremental() {
+        assert_eq!(
+            moves("G91 G0 X1 Y2\nX1 Y-1\nG90 X0\n"),
+            [
+                Move::Rapid(point(1, 2, 0)),
+                Move::Rapid(point(2, 1, 0)),
+                Move::Rapid(point(0, 1, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn interpreter_inches() {
+        assert_eq!(
+            moves("G20 G1 X1 F10\n"),
+            [Move::Linear(
+                Point::new(Decimal::new(254, 1), Decimal::new(0, 0), Decimal::new(0, 0)),
+                Decimal::new(254, 0),
+            )]
+        );
+        let interpreter = interpret(Vec::new(), "G93 G20 F2\n");
+        assert_eq!(interpreter.state().feed_rate(), Decimal::new(2, 0));
+    }
+
+    #[test]
+    fn interpreter_work_offset() {
+        let mut interpreter = Interpreter::new(Vec::new());
+        interpreter.set_work_offset(1, point(10, 20, 30));
+        BlockParser::<i32>::default()
+            .try_feed_str("G55 G0 X1\nG54 Y1\n", &mut interpreter)
+            .unwrap();
+        assert_eq!(
+            interpreter.machine()[1..],
+            [
+                Move::Rapid(point(11, 0, 0)),
+                Move::ModalState(SpindleDirection::Stopped),
+                Move::Rapid(point(11, 1, 0))
+            ]
+        );
+        assert_eq!(interpreter.state().work_offset(1), Some(point(10, 20, 30)));
+        assert_eq!(interpreter.state().work_offset(9), None);
+    }
+
+    #[test]
+    fn interpreter_axis_offset() {
+        let interpreter = interpret(Vec::new(), "G0 X5 Y5\nG92 X0\nX1\n");
+        let state = interpreter.state();
+        assert_eq!(state.axis_offset(), point(5, 0, 0));
+        assert_eq!(state.position(), point(6, 5, 0));
+        assert_eq!(state.program_position(), Some(point(1, 5, 0)));
+        let interpreter = interpret(Vec::new(), "G0 X5 Y5\nG92 X0\nG92.1\nX1\n");
+        assert_eq!(interpreter.state().position(), point(1, 5, 0));
+    }
+
+    #[test]
+    fn interpreter_arc() {
+        let arc = Arc::new(
+            point(1, 0, 0),
+            point(0, 1, 1),
+            point(0, 0, 0),
+            Plane::XY,
+            false,
+        );
+        assert_eq!(
+            moves("G0 X1\nG3 X0 Y1 Z1 I-1 F50\n")[1..],
+            [Move::Arc(arc, Decimal::new(50, 0))]
+        );
+        let arc = Arc::new(
+            point(1, 0, 0),
+            point(1, 0, 2),
+            point(1, 0, 1),
+            Plane::ZX,
+            true,
+        );
+        assert_eq!(
+            moves("G0 X1\nG18 G90.1 G2 Z2 I1 K1 F50\n")[1..],
+            [Move::Arc(arc, Decimal::new(50, 0))]
+        );
+        let arc = Arc::new(
+            point(10, 0, 0),
+            point(0, -10, 0),
+            point(0, 0, 0),
+            Plane::XY,
+            true,
+        );
+        assert_eq!(
+            moves("G0 X10\nG2 X0 Y-10 R10 F50\n")[1..],
+            [Move::Arc(arc, Decimal::new(50, 0))]
+        );
+    }
+
+    #[test]
+    fn interpreter_order() {
+        assert_eq!(
+            interpret(Vec::new(), "G1 X1 M3 M2 F1 G4 P0.5 M6\n").into_machine(),
+            [
+                Move::MCode(Decimal::new(6, 0)),
+                Move::ModalState(SpindleDirection::Clockwise),
+                Move::Dwell(Decimal::new(5, 1)),
+                Move::Linear(point(1, 0, 0), Decimal::new(1, 0)),
+                Move::MCode(Decimal::new(2, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn interpreter_tool_change() {
+        assert_eq!(
+            moves("M6 T3\n"),
+            [
+                Move::Tool(Decimal::new(3, 0)),
+                Move::MCode(Decimal::new(6, 0))
+            ]
+        );
+        assert_eq!(
+            moves("T3 M6\n"),
+            [
+                Move::Tool(Decimal::new(3, 0)),
+                Move::MCode(Decimal::new(6, 0))
+            ]
+        );
+    }
+
+    #[test]
+    fn interpreter_m_codes() {
+        assert_eq!(
+            moves("M104 M106 M3\n"),
+            [
+                Move::MCode(Decimal::new(104, 0)),
+                Move::MCode(Decimal::new(106, 0)),
+            ]
+        );
+        assert_eq!(
+            error(Vec::new(), "M100 M101 M102 M103 M104\n"),
+            InterpreterError::Unsupported
+        );
+    }
+
+    #[test]
+    fn interpreter_errors() {
+        assert_eq!(
+            error(Vec::new(), "G0 G1\n"),
+            InterpreterError::ModalGroupConflict(ModalGroup::Motion)
+        );
+        assert_eq!(
+            error(Vec::new(), "X1 x2\n"),
+            InterpreterError::DuplicateWord('X')
+        );
+        assert_eq!(
+            error(Vec::new(), "G4\n"),
+            InterpreterError::MissingWord('P')
+        );
+        assert_eq!(
+            error(Vec::new(), "G80 X1\n"),
+            InterpreterError::NoMotionMode
+        );
+        assert_eq!(
+            error(Vec::new(), "G2 X1 K1\n"),
+            InterpreterError::InvalidArc
+        );
+        assert_eq!(
+            error(Vec::new(), "G2 X4 R1\n"),
+            InterpreterError::InvalidArc
+        );
+        assert_eq!(
+            error(Vec::new(), "G2 X0 R1\n"),
+            InterpreterError::InvalidArc
+        );
+        assert_eq!(error(Vec::new(), "G41\n"), InterpreterError::Unsupported);
+        assert_eq!(error(Vec::new(), "G5\n"), InterpreterError::Unsupported);
+        assert_eq!(error(Vec::new(), "E1\n"), InterpreterError::Unsupported);
+        assert_eq!(
+            error(Vec::new(), "X2147483647\nG91 X1\n"),
+            InterpreterError::Overflow
+        );
+    }
+
+    #[test]
+    fn interpreter_invalid_block() {
+        let config = Config::new().with_recovery(true);
+        let mut parser = BlockParser::<i32>::new(config);
+        let mut interpreter = Interpreter::new(Vec::new());
+        parser.try_feed_str("G0 X1\n", &mut interpreter).unwrap();
+        let state = interpreter.state().clone();
+        let len = interpreter.machine().len();
+        for block in [
+            "G91 G20 M6 G2 X1 K1\n",
+            "M6 S100 G41\n",
+            "G91 G99 G81 X1000000000 Z-1 R1 L3 M6\n",
+        ] {
+            assert!(parser.try_feed_str(block, &mut interpreter).is_err());
+            assert_eq!(interpreter.state(), &state);
+            assert_eq!(interpreter.machine().len(), len);
+        }
+        parser.try_feed_str("Y1\n", &mut interpreter).unwrap();
+        assert_eq!(interpreter.state().position(), point(1, 1, 0));
+    }
+
+    #[test]
+    fn interpreter_abort() {
+        let config = Config::new().with_recovery(true);
+        let mut parser = BlockParser::<i32>::new(config);
+        let mut interpreter = Interpreter::new(Vec::new());
+        assert!(parser.try_feed_str("G1 X1 $", &mut interpreter).is_err());
+        parser.try_feed_str("\nY1\n", &mut interpreter).unwrap();
+        assert_eq!(interpreter.state().position(), point(0, 1, 0));
+    }
+}
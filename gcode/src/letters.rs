@@ -0,0 +1,72 @@
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// A fixed-capacity buffer of ASCII letters, used to recognize names and keywords.
+///
+/// The letters are converted to upper case.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Letters<const N: usize> {
+    letters: [u8; N],
+    len: u8,
+}
+
+impl<const N: usize> Default for Letters<N> {
+    fn default() -> Self {
+        Self {
+            letters: [0; N],
+            len: 0,
+        }
+    }
+}
+
+impl<const N: usize> Letters<N> {
+    /// Appends a letter. Returns `false` if the character is not an ASCII letter or if the buffer
+    /// is full.
+    pub fn try_push(&mut self, c: char) -> bool {
+        let len = usize::from(self.len);
+        if c.is_ascii_alphabetic() && len < N {
+            // The `as` conversion cannot truncate an ASCII character.
+            #[allow(clippy::cast_possible_truncation)]
+            let letter = c.to_ascii_uppercase() as u8;
+            self.letters[len] = letter;
+            self.len += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the letters.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.letters[..usize::from(self.len)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_push() {
+        let mut letters = Letters::<3>::default();
+        assert!(letters.try_push('a'));
+        assert!(letters.try_push('B'));
+        assert_eq!(letters.as_bytes(), b"AB");
+    }
+
+    #[test]
+    fn try_push_full() {
+        let mut letters = Letters::<1>::default();
+        assert!(letters.try_push('a'));
+        assert!(!letters.try_push('b'));
+        assert_eq!(letters.as_bytes(), b"A");
+    }
+
+    #[test]
+    fn try_push_not_letter() {
+        let mut letters = Letters::<3>::default();
+        assert!(!letters.try_push('1'));
+        assert_eq!(letters.as_bytes(), b"");
+    }
+}
@@ -1,6 +1,6 @@
 #![no_std]
+#![feature(associated_type_defaults)]
 #![feature(const_trait_impl)]
-#![feature(try_blocks)]
 #![warn(clippy::cargo, clippy::nursery, clippy::pedantic)]
 #![allow(
     clippy::missing_errors_doc,
@@ -13,11 +13,50 @@
 //! ## Feature flags
 #![doc = document_features::document_features!()]
 
+mod arc;
 mod block;
+mod buffer;
+mod collector;
+mod config;
+mod cycle;
 mod decimal;
+mod digits;
+mod event;
+mod expression;
+#[cfg(feature = "float")]
+mod float;
+mod format;
+mod interpreter;
+mod letters;
+mod machine;
+mod modal;
+mod o_word;
+#[cfg(feature = "position")]
+mod position;
 mod sign;
 mod significand;
+#[cfg(test)]
+mod testing;
+mod words;
+mod writer;
 
-pub use crate::block::{BlockBuilder, BlockParser, Error, ErrorKind};
-pub use crate::decimal::Decimal;
+pub use crate::arc::{radius_center, ArcError, ArcSegments, Linearizer, LinearizerError};
+pub use crate::block::{BlockBuilder, BlockParser, CommentKind, Error, ErrorKind};
+pub use crate::collector::{Block, BlockCollector, CollectorError, Word};
+pub use crate::config::Config;
+pub use crate::cycle::{CannedCycle, ReturnMode};
+pub use crate::decimal::{Decimal, Rounded, Rounding};
+pub use crate::event::{Event, Events};
+pub use crate::expression::{BinaryOperator, ExpressionToken, Function};
+pub use crate::format::Fixed;
+pub use crate::interpreter::{
+    DistanceMode, FeedRateMode, Interpreter, InterpreterError, MachineState, MotionMode,
+    SpindleDirection, Units, COORDINATE_SYSTEMS,
+};
+pub use crate::machine::{Arc, Machine, Plane, Point};
+pub use crate::modal::{ModalError, ModalGroup, ModalValidator};
+pub use crate::o_word::OWordKeyword;
+#[cfg(feature = "position")]
+pub use crate::position::Position;
 pub use crate::significand::Significand;
+pub use crate::writer::{BlockWriter, WriterConfig, WriterError};
@@ -0,0 +1,309 @@
+use crate::decimal::Decimal;
+use crate::interpreter::MachineState;
+use crate::significand::Significand;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// The trait for types that receive the resolved motion commands of the [`Interpreter`].
+///
+/// All coordinates are absolute machine coordinates in millimeters. The [`Interpreter`] has
+/// already applied the units, the distance mode, and the offsets of the program.
+///
+/// [`Interpreter`]: crate::Interpreter
+pub trait Machine {
+    /// The type of errors generated by the [`Machine`] itself.
+    type Error;
+
+    /// The type used as the significand for [`Decimal`] numbers.
+    type Significand: Significand;
+
+    /// Called for a rapid move (`G0`) to `end`.
+    fn rapid(&mut self, end: Point<Self::Significand>) -> Result<(), Self::Error>;
+
+    /// Called for a straight move (`G1`) to `end` at the feed rate.
+    ///
+    /// The feed rate is in millimeters per minute with `G94`, millimeters per revolution with
+    /// `G95`, and moves per minute (i.e., the inverse of the duration of the move) with `G93`.
+    fn linear(
+        &mut self,
+        end: Point<Self::Significand>,
+        feed_rate: Decimal<Self::Significand>,
+    ) -> Result<(), Self::Error>;
+
+    /// Called for a circular or helical move (`G2` or `G3`) at the feed rate.
+    ///
+    /// See [`Machine::linear`] for the feed rate. A [`Machine`] that only moves in straight lines
+    /// can be wrapped in a [`Linearizer`].
+    ///
+    /// [`Linearizer`]: crate::Linearizer
+    fn arc(
+        &mut self,
+        arc: Arc<Self::Significand>,
+        feed_rate: Decimal<Self::Significand>,
+    ) -> Result<(), Self::Error>;
+
+    /// Called for a dwell (`G4`) of the duration in seconds.
+    ///
+    /// The default implementation does nothing.
+    fn dwell(&mut self, seconds: Decimal<Self::Significand>) -> Result<(), Self::Error> {
+        let _ = seconds;
+        Ok(())
+    }
+
+    /// Called for the `T` word of a block, before its M codes, so that the tool is selected before
+    /// a tool change (`M6`) in the same block.
+    ///
+    /// The default implementation does nothing.
+    fn select_tool(&mut self, tool: Decimal<Self::Significand>) -> Result<(), Self::Error> {
+        let _ = tool;
+        Ok(())
+    }
+
+    /// Called for each M code that the [`Interpreter`] does not handle itself (i.e., every M code
+    /// except spindle control and coolant.) The stopping M codes (e.g., `M2`) are passed after the
+    /// motion of the block, and all others before it.
+    ///
+    /// The default implementation does nothing.
+    ///
+    /// [`Interpreter`]: crate::Interpreter
+    fn m_code(&mut self, number: Decimal<Self::Significand>) -> Result<(), Self::Error> {
+        let _ = number;
+        Ok(())
+    }
+
+    /// Called for each block after the modal state of the block is applied, and before the motion
+    /// of the block (e.g., to start the spindle before a cut.)
+    ///
+    /// The default implementation does nothing.
+    fn modal_state(&mut self, state: &MachineState<Self::Significand>) -> Result<(), Self::Error> {
+        let _ = state;
+        Ok(())
+    }
+}
+
+/// The number of axes (`X`, `Y`, and `Z`.)
+pub const AXES: usize = 3;
+
+/// A point in machine coordinates.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Point<S>
+where
+    S: Significand,
+{
+    axes: [Decimal<S>; AXES],
+}
+
+impl<S> Default for Point<S>
+where
+    S: Significand,
+{
+    /// Returns the origin.
+    fn default() -> Self {
+        Self::new(zero(), zero(), zero())
+    }
+}
+
+impl<S> Point<S>
+where
+    S: Significand,
+{
+    /// Creates a new [`Point`].
+    #[must_use]
+    pub const fn new(x: Decimal<S>, y: Decimal<S>, z: Decimal<S>) -> Self {
+        Self { axes: [x, y, z] }
+    }
+
+    /// Returns the `X` coordinate.
+    #[must_use]
+    pub const fn x(&self) -> Decimal<S> {
+        self.axes[0]
+    }
+
+    /// Returns the `Y` coordinate.
+    #[must_use]
+    pub const fn y(&self) -> Decimal<S> {
+        self.axes[1]
+    }
+
+    /// Returns the `Z` coordinate.
+    #[must_use]
+    pub const fn z(&self) -> Decimal<S> {
+        self.axes[2]
+    }
+
+    /// Checked addition of each coordinate. Returns `None` if overflow occurred.
+    #[must_use]
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.zip(rhs, Decimal::checked_add)
+    }
+
+    /// Checked subtraction of each coordinate. Returns `None` if overflow occurred.
+    #[must_use]
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.zip(rhs, Decimal::checked_sub)
+    }
+
+    /// Returns the coordinate of an axis (`0` for `X`, `1` for `Y`, and `2` for `Z`.)
+    pub(crate) const fn axis(&self, axis: usize) -> Decimal<S> {
+        self.axes[axis]
+    }
+
+    pub(crate) const fn set_axis(&mut self, axis: usize, value: Decimal<S>) {
+        self.axes[axis] = value;
+    }
+
+    fn zip<F>(self, rhs: Self, f: F) -> Option<Self>
+    where
+        F: Fn(Decimal<S>, Decimal<S>) -> Option<Decimal<S>>,
+    {
+        let [x, y, z] = self.axes;
+        let [rhs_x, rhs_y, rhs_z] = rhs.axes;
+        Some(Self::new(f(x, rhs_x)?, f(y, rhs_y)?, f(z, rhs_z)?))
+    }
+}
+
+/// The planes of circular moves.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum Plane {
+    /// The XY plane (`G17`.)
+    #[default]
+    XY,
+
+    /// The ZX plane (`G18`.)
+    ZX,
+
+    /// The YZ plane (`G19`.)
+    YZ,
+}
+
+impl Plane {
+    /// Returns the axes of the plane, in the order of its first and second directions, and the
+    /// axis normal to it.
+    pub(crate) const fn axes(self) -> (usize, usize, usize) {
+        match self {
+            Self::XY => (0, 1, 2),
+            Self::ZX => (2, 0, 1),
+            Self::YZ => (1, 2, 0),
+        }
+    }
+}
+
+/// A circular or helical move.
+///
+/// The move turns around the center in the [`Plane`], from `start` to `end`. The coordinate of
+/// `end` along the axis normal to the plane may differ from that of `start`, for a helical move.
+/// The center has the same normal coordinate as `start`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Arc<S>
+where
+    S: Significand,
+{
+    start: Point<S>,
+    end: Point<S>,
+    center: Point<S>,
+    plane: Plane,
+    clockwise: bool,
+}
+
+impl<S> Arc<S>
+where
+    S: Significand,
+{
+    /// Creates a new [`Arc`].
+    #[must_use]
+    pub const fn new(
+        start: Point<S>,
+        end: Point<S>,
+        center: Point<S>,
+        plane: Plane,
+        clockwise: bool,
+    ) -> Self {
+        Self {
+            start,
+            end,
+            center,
+            plane,
+            clockwise,
+        }
+    }
+
+    /// Returns the start of the move.
+    #[must_use]
+    pub const fn start(&self) -> Point<S> {
+        self.start
+    }
+
+    /// Returns the end of the move.
+    #[must_use]
+    pub const fn end(&self) -> Point<S> {
+        self.end
+    }
+
+    /// Returns the center of the circle.
+    #[must_use]
+    pub const fn center(&self) -> Point<S> {
+        self.center
+    }
+
+    /// Returns the plane of the circle.
+    #[must_use]
+    pub const fn plane(&self) -> Plane {
+        self.plane
+    }
+
+    /// Returns `true` if the move turns clockwise (`G2`), as seen from the positive end of the
+    /// axis normal to the plane, or `false` if it turns counterclockwise (`G3`.)
+    #[must_use]
+    pub const fn is_clockwise(&self) -> bool {
+        self.clockwise
+    }
+}
+
+/// Returns zero.
+pub fn zero<S>() -> Decimal<S>
+where
+    S: Significand,
+{
+    Decimal::new(S::default(), 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::testing::point;
+
+    #[test]
+    fn point_default() {
+        assert_eq!(Point::default(), point(0, 0, 0));
+    }
+
+    #[test]
+    fn point_checked_add() {
+        assert_eq!(
+            point(1, 2, 3).checked_add(point(4, 5, 6)),
+            Some(point(5, 7, 9))
+        );
+        assert_eq!(point(i32::MAX, 0, 0).checked_add(point(1, 0, 0)), None);
+    }
+
+    #[test]
+    fn point_checked_sub() {
+        assert_eq!(
+            point(1, 2, 3).checked_sub(point(4, 5, 6)),
+            Some(point(-3, -3, -3))
+        );
+        assert_eq!(point(0, 0, i32::MIN).checked_sub(point(0, 0, 1)), None);
+    }
+
+    #[test]
+    fn plane_axes() {
+        assert_eq!(Plane::XY.axes(), (0, 1, 2));
+        assert_eq!(Plane::ZX.axes(), (2, 0, 1));
+        assert_eq!(Plane::YZ.axes(), (1, 2, 0));
+    }
+}
@@ -0,0 +1,610 @@
+use crate::block::{BlockBuilder, CommentKind};
+use crate::decimal::Decimal;
+use crate::digits::Digits;
+use crate::expression::ExpressionToken;
+use crate::o_word::OWordKeyword;
+#[cfg(feature = "position")]
+use crate::position::Position;
+use crate::significand::Significand;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// The modal groups of G and M codes, as defined by RS-274/NGC.
+///
+/// At most one code of each group can appear in a block. The codes of the non-modal group
+/// ([`ModalGroup::NonModal`]) are not modal, but they also cannot be combined.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum ModalGroup {
+    /// Group 0: `G4`, `G10`, `G28`, `G28.1`, `G30`, `G30.1`, `G53`, `G92`, `G92.1`, `G92.2`, and
+    /// `G92.3`.
+    NonModal,
+
+    /// Group 1, motion: `G0`, `G1`, `G2`, `G3`, `G33`, `G33.1`, `G38.2` to `G38.5`, `G73`,
+    /// `G76`, and `G80` to `G89`.
+    Motion,
+
+    /// Group 2, plane selection: `G17`, `G18`, `G19`, `G17.1`, `G18.1`, and `G19.1`.
+    Plane,
+
+    /// Group 3, distance mode: `G90` and `G91`.
+    Distance,
+
+    /// Group 4, arc distance mode: `G90.1` and `G91.1`.
+    ArcDistance,
+
+    /// Group 5, feed rate mode: `G93`, `G94`, and `G95`.
+    FeedRateMode,
+
+    /// Group 6, units: `G20` and `G21`.
+    Units,
+
+    /// Group 7, cutter radius compensation: `G40`, `G41`, `G42`, `G41.1`, and `G42.1`.
+    CutterCompensation,
+
+    /// Group 8, tool length offset: `G43`, `G43.1`, and `G49`.
+    ToolLengthOffset,
+
+    /// Group 10, canned cycle return mode: `G98` and `G99`.
+    ReturnMode,
+
+    /// Group 12, coordinate system selection: `G54` to `G59`, `G59.1`, `G59.2`, and `G59.3`.
+    CoordinateSystem,
+
+    /// Group 13, path control mode: `G61`, `G61.1`, and `G64`.
+    PathControl,
+
+    /// Group 14, spindle speed mode: `G96` and `G97`.
+    SpindleSpeedMode,
+
+    /// M group 4, stopping: `M0`, `M1`, `M2`, `M30`, and `M60`.
+    Stopping,
+
+    /// M group 6, tool change: `M6`.
+    ToolChange,
+
+    /// M group 7, spindle control: `M3`, `M4`, and `M5`.
+    Spindle,
+
+    /// M group 8, coolant: `M7`, `M8`, and `M9`. `M7` and `M8` may appear in the same block.
+    Coolant,
+
+    /// M group 9, override switches: `M48` and `M49`.
+    Override,
+}
+
+impl ModalGroup {
+    /// All the modal groups, in the order of [`ModalGroup::index`].
+    pub(crate) const ALL: [Self; 18] = [
+        Self::NonModal,
+        Self::Motion,
+        Self::Plane,
+        Self::Distance,
+        Self::ArcDistance,
+        Self::FeedRateMode,
+        Self::Units,
+        Self::CutterCompensation,
+        Self::ToolLengthOffset,
+        Self::ReturnMode,
+        Self::CoordinateSystem,
+        Self::PathControl,
+        Self::SpindleSpeedMode,
+        Self::Stopping,
+        Self::ToolChange,
+        Self::Spindle,
+        Self::Coolant,
+        Self::Override,
+    ];
+
+    /// Returns the modal group of a G code, or `None` if the G code is unknown.
+    #[must_use]
+    pub fn of_g_code<S>(number: Decimal<S>) -> Option<Self>
+    where
+        S: Significand,
+    {
+        match code(number)? {
+            (4 | 10 | 28 | 30 | 53 | 92, 0) | (28 | 30, 1) | (92, 1..=3) => Some(Self::NonModal),
+            (0..=3 | 33 | 73 | 76 | 80..=89, 0) | (33, 1) | (38, 2..=5) => Some(Self::Motion),
+            (17..=19, 0 | 1) => Some(Self::Plane),
+            (90 | 91, 0) => Some(Self::Distance),
+            (90 | 91, 1) => Some(Self::ArcDistance),
+            (93..=95, 0) => Some(Self::FeedRateMode),
+            (20 | 21, 0) => Some(Self::Units),
+            (40..=42, 0) | (41 | 42, 1) => Some(Self::CutterCompensation),
+            (43 | 49, 0) | (43, 1) => Some(Self::ToolLengthOffset),
+            (98 | 99, 0) => Some(Self::ReturnMode),
+            (54..=59, 0) | (59, 1..=3) => Some(Self::CoordinateSystem),
+            (61 | 64, 0) | (61, 1) => Some(Self::PathControl),
+            (96 | 97, 0) => Some(Self::SpindleSpeedMode),
+            _ => None,
+        }
+    }
+
+    /// Returns the modal group of an M code, or `None` if the M code is unknown.
+    #[must_use]
+    pub fn of_m_code<S>(number: Decimal<S>) -> Option<Self>
+    where
+        S: Significand,
+    {
+        match code(number)? {
+            (0 | 1 | 2 | 30 | 60, 0) => Some(Self::Stopping),
+            (6, 0) => Some(Self::ToolChange),
+            (3..=5, 0) => Some(Self::Spindle),
+            (7..=9, 0) => Some(Self::Coolant),
+            (48 | 49, 0) => Some(Self::Override),
+            _ => None,
+        }
+    }
+
+    /// Returns the position of the modal group in [`ModalGroup::ALL`].
+    pub(crate) const fn index(self) -> usize {
+        self as usize
+    }
+
+    const fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// Splits the number of a code into its integer part and its tenths (e.g., `(38, 2)` for
+/// `G38.2`.) Returns `None` if the number is negative or has more than one significant digit
+/// right of the decimal point.
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) fn code<S>(number: Decimal<S>) -> Option<(u16, u16)>
+where
+    S: Significand,
+{
+    if number.significand().is_zero() {
+        return Some((0, 0));
+    }
+    if number.significand().is_negative() {
+        return None;
+    }
+    let digits = Digits::new(number.significand());
+    let text = digits.as_str();
+    let negative_exponent = usize::try_from(number.negative_exponent()).ok()?;
+    let (integer, fraction) = text.split_at(text.len().checked_sub(negative_exponent)?);
+    let integer = if integer.is_empty() {
+        0
+    } else {
+        integer.parse().ok()?
+    };
+    let tenths = match fraction.trim_end_matches('0').as_bytes() {
+        [] => 0,
+        [digit] => u16::from(digit - b'0'),
+        _ => return None,
+    };
+    Some((integer, tenths))
+}
+
+/// The axis addresses.
+const AXES: &str = "XYZABCUVW";
+
+/// The error type for the [`ModalValidator`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum ModalError<E> {
+    /// The inner [`BlockBuilder`] produced an error.
+    BlockBuilderError(E),
+
+    /// The block has two codes of the same [`ModalGroup`] (e.g., `G0 G1`.)
+    ModalGroupConflict(ModalGroup),
+
+    /// The block has two words with the same axis address (e.g., `X1 X2`.) The address is in
+    /// upper case.
+    DuplicateAxis(char),
+}
+
+impl<E> Eq for ModalError<E> where E: Eq {}
+
+/// A [`BlockBuilder`] that checks the modal groups and axis words of each block before it
+/// forwards the events to an inner [`BlockBuilder`].
+///
+/// Each G or M code is checked against the other codes of the block (see [`ModalGroup`]), except
+/// that `M7` and `M8` may appear together. Unknown codes are not checked. Each word with an axis
+/// address (`X`, `Y`, `Z`, `A`, `B`, `C`, `U`, `V`, or `W`) and without an index is checked
+/// against the other axis words of the block. The event that causes a conflict is not forwarded.
+///
+///     use gcode::{BlockParser, BlockCollector, ErrorKind, ModalError, ModalGroup, ModalValidator};
+///
+///     let mut parser = BlockParser::<i32>::default();
+///     let mut validator = ModalValidator::new(BlockCollector::<i32>::default());
+///     let error = parser.try_feed_str("G20 G21\n", &mut validator).unwrap_err();
+///     assert_eq!(
+///         error.kind(),
+///         &ErrorKind::BlockBuilderError(ModalError::ModalGroupConflict(ModalGroup::Units))
+///     );
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct ModalValidator<B> {
+    builder: B,
+    groups: u32,
+    coolant: u8,
+    axes: u16,
+}
+
+impl<B> ModalValidator<B>
+where
+    B: BlockBuilder,
+{
+    /// Creates a new [`ModalValidator`] that forwards events to `builder`.
+    pub const fn new(builder: B) -> Self {
+        Self {
+            builder,
+            groups: 0,
+            coolant: 0,
+            axes: 0,
+        }
+    }
+
+    /// Returns a reference to the inner builder.
+    pub const fn builder(&self) -> &B {
+        &self.builder
+    }
+
+    /// Returns a mutable reference to the inner builder.
+    pub const fn builder_mut(&mut self) -> &mut B {
+        &mut self.builder
+    }
+
+    /// Returns the inner builder.
+    pub fn into_builder(self) -> B {
+        self.builder
+    }
+
+    const fn check_group(&mut self, group: Option<ModalGroup>) -> Result<(), ModalError<B::Error>> {
+        if let Some(group) = group {
+            if 0 != self.groups & group.bit() {
+                return Err(ModalError::ModalGroupConflict(group));
+            }
+            self.groups |= group.bit();
+        }
+        Ok(())
+    }
+
+    /// Checks the group of an M code. Mist (`M7`) and flood (`M8`) coolant may be turned on in
+    /// the same block.
+    fn check_m_code(
+        &mut self,
+        number: Decimal<B::Significand>,
+    ) -> Result<(), ModalError<B::Error>> {
+        let group = ModalGroup::of_m_code(number);
+        if Some(ModalGroup::Coolant) != group {
+            return self.check_group(group);
+        }
+        // One bit each for `M7`, `M8`, and `M9`.
+        let bit = match code(number) {
+            Some((7, 0)) => 0b001,
+            Some((8, 0)) => 0b010,
+            _ => 0b100,
+        };
+        let coolant = self.coolant | bit;
+        if 0 != self.coolant & bit || (0 != coolant & 0b100 && 0b100 != coolant) {
+            return Err(ModalError::ModalGroupConflict(ModalGroup::Coolant));
+        }
+        self.coolant = coolant;
+        Ok(())
+    }
+
+    fn check_axis(
+        &mut self,
+        address: char,
+        index: Option<B::Index>,
+    ) -> Result<(), ModalError<B::Error>> {
+        let address = address.to_ascii_uppercase();
+        if let (None, Some(axis)) = (index, AXES.find(address)) {
+            let bit = 1 << axis;
+            if 0 != self.axes & bit {
+                return Err(ModalError::DuplicateAxis(address));
+            }
+            self.axes |= bit;
+        }
+        Ok(())
+    }
+
+    const fn reset(&mut self) {
+        self.groups = 0;
+        self.coolant = 0;
+        self.axes = 0;
+    }
+}
+
+impl<B> BlockBuilder for ModalValidator<B>
+where
+    B: BlockBuilder,
+{
+    type Error = ModalError<B::Error>;
+    type Significand = B::Significand;
+    type Index = B::Index;
+    type SequenceNumber = B::SequenceNumber;
+
+    fn program_start(&mut self) -> Result<(), Self::Error> {
+        self.builder
+            .program_start()
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn sequence_number(
+        &mut self,
+        alignment: bool,
+        number: Decimal<Self::SequenceNumber>,
+    ) -> Result<(), Self::Error> {
+        self.builder
+            .sequence_number(alignment, number)
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn g_code(&mut self, number: Decimal<Self::Significand>) -> Result<(), Self::Error> {
+        self.check_group(ModalGroup::of_g_code(number))?;
+        self.builder
+            .g_code(number)
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn m_code(&mut self, number: Decimal<Self::Significand>) -> Result<(), Self::Error> {
+        self.check_m_code(number)?;
+        self.builder
+            .m_code(number)
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn data(
+        &mut self,
+        address: char,
+        index: Option<Self::Index>,
+        number: Decimal<Self::Significand>,
+    ) -> Result<(), Self::Error> {
+        self.check_axis(address, index)?;
+        self.builder
+            .data(address, index, number)
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn parameter_word(
+        &mut self,
+        address: char,
+        index: Option<Self::Index>,
+        parameter: Self::Index,
+    ) -> Result<(), Self::Error> {
+        self.check_axis(address, index)?;
+        self.builder
+            .parameter_word(address, index, parameter)
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn parameter_assignment(
+        &mut self,
+        parameter: Self::Index,
+        number: Decimal<Self::Significand>,
+    ) -> Result<(), Self::Error> {
+        self.builder
+            .parameter_assignment(parameter, number)
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn expression(
+        &mut self,
+        token: ExpressionToken<Self::Significand, Self::Index>,
+    ) -> Result<(), Self::Error> {
+        self.builder
+            .expression(token)
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn expression_word(
+        &mut self,
+        address: char,
+        index: Option<Self::Index>,
+    ) -> Result<(), Self::Error> {
+        self.check_axis(address, index)?;
+        self.builder
+            .expression_word(address, index)
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn expression_assignment(&mut self, parameter: Self::Index) -> Result<(), Self::Error> {
+        self.builder
+            .expression_assignment(parameter)
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn o_word(&mut self, number: Self::Index, keyword: OWordKeyword) -> Result<(), Self::Error> {
+        self.builder
+            .o_word(number, keyword)
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn o_word_argument(&mut self) -> Result<(), Self::Error> {
+        self.builder
+            .o_word_argument()
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn checksum(&mut self, checksum: u8, valid: bool) -> Result<(), Self::Error> {
+        self.builder
+            .checksum(checksum, valid)
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn end_block(&mut self) -> Result<(), Self::Error> {
+        self.reset();
+        self.builder
+            .end_block()
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn abort_block(&mut self) -> Result<(), Self::Error> {
+        self.reset();
+        self.builder
+            .abort_block()
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn comment_start(&mut self, kind: CommentKind) -> Result<(), Self::Error> {
+        self.builder
+            .comment_start(kind)
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn comment_char(&mut self, c: char) -> Result<(), Self::Error> {
+        self.builder
+            .comment_char(c)
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn comment_end(&mut self) -> Result<(), Self::Error> {
+        self.builder
+            .comment_end()
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    fn end_line(&mut self) -> Result<(), Self::Error> {
+        self.builder
+            .end_line()
+            .map_err(ModalError::BlockBuilderError)
+    }
+
+    #[cfg(feature = "position")]
+    fn word_start(&mut self, position: Position) -> Result<(), Self::Error> {
+        self.builder
+            .word_start(position)
+            .map_err(ModalError::BlockBuilderError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::block::{BlockParser, Error, ErrorKind};
+    use crate::collector::{BlockCollector, CollectorError};
+    use crate::config::Config;
+
+    type Validator = ModalValidator<BlockCollector<i32>>;
+
+    fn validate(
+        validator: &mut Validator,
+        s: &str,
+    ) -> Result<(), Error<ModalError<CollectorError>>> {
+        let config = Config::default().with_parameters(true);
+        BlockParser::<i32>::new(config).try_feed_str(s, validator)
+    }
+
+    fn error(s: &str) -> ModalError<CollectorError> {
+        let mut validator = ModalValidator::new(BlockCollector::default());
+        match validate(&mut validator, s).unwrap_err().kind() {
+            ErrorKind::BlockBuilderError(error) => *error,
+            kind => panic!("{kind:?}"),
+        }
+    }
+
+    #[test]
+    fn modal_group_of_g_code() {
+        let cases = [
+            (Decimal::new(0, 0), Some(ModalGroup::Motion)),
+            (Decimal::new(10, 1), Some(ModalGroup::Motion)),
+            (Decimal::new(382, 1), Some(ModalGroup::Motion)),
+            (Decimal::new(381, 1), None),
+            (Decimal::new(90, 0), Some(ModalGroup::Distance)),
+            (Decimal::new(901, 1), Some(ModalGroup::ArcDistance)),
+            (Decimal::new(9210, 2), Some(ModalGroup::NonModal)),
+            (Decimal::new(9_215, 2), None),
+            (Decimal::new(593, 1), Some(ModalGroup::CoordinateSystem)),
+            (Decimal::new(0, 3), Some(ModalGroup::Motion)),
+            (Decimal::new(5, 2), None),
+            (Decimal::new(-1, 0), None),
+            (Decimal::new(100_000, 0), None),
+        ];
+        for (number, group) in cases {
+            assert_eq!(ModalGroup::of_g_code(number), group, "{number}");
+        }
+    }
+
+    #[test]
+    fn modal_group_of_m_code() {
+        assert_eq!(
+            ModalGroup::of_m_code(Decimal::new(30_u8, 0)),
+            Some(ModalGroup::Stopping)
+        );
+        assert_eq!(
+            ModalGroup::of_m_code(Decimal::new(4_u8, 0)),
+            Some(ModalGroup::Spindle)
+        );
+        assert_eq!(ModalGroup::of_m_code(Decimal::new(31, 1)), None);
+        assert_eq!(ModalGroup::of_m_code(Decimal::new(100_u8, 0)), None);
+    }
+
+    #[test]
+    fn modal_validator() {
+        let mut validator = ModalValidator::new(BlockCollector::default());
+        validate(&mut validator, "G0 G90 G21 X1 Y2 M3 M8\n").unwrap();
+        let block = validator.builder_mut().take_block().unwrap();
+        assert_eq!(block.g_codes().len(), 3);
+        assert_eq!(block.words().len(), 2);
+        for s in ["G1 X2\n", "G1 X3\n"] {
+            validate(&mut validator, s).unwrap();
+            assert!(validator.builder_mut().take_block().is_some());
+        }
+    }
+
+    #[test]
+    fn modal_validator_conflict() {
+        assert_eq!(
+            error("G0 G1 X1\n"),
+            ModalError::ModalGroupConflict(ModalGroup::Motion)
+        );
+        assert_eq!(
+            error("G20 G21\n"),
+            ModalError::ModalGroupConflict(ModalGroup::Units)
+        );
+        assert_eq!(
+            error("M3 M5\n"),
+            ModalError::ModalGroupConflict(ModalGroup::Spindle)
+        );
+    }
+
+    #[test]
+    fn modal_validator_coolant() {
+        let mut validator = ModalValidator::new(BlockCollector::default());
+        for s in ["M7 M8\n", "M8 M7\n", "M9\n"] {
+            validate(&mut validator, s).unwrap();
+            assert!(validator.builder_mut().take_block().is_some());
+        }
+        for s in ["M8 M9\n", "M9 M7\n", "M7 M7\n", "M7 M8 M9\n", "M8 M7 M8\n"] {
+            assert_eq!(
+                error(s),
+                ModalError::ModalGroupConflict(ModalGroup::Coolant),
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    fn modal_validator_duplicate_axis() {
+        assert_eq!(error("G1 X1 x2\n"), ModalError::DuplicateAxis('X'));
+        assert_eq!(error("G1 Z1 Z#2\n"), ModalError::DuplicateAxis('Z'));
+        let mut validator = ModalValidator::new(BlockCollector::default());
+        validate(&mut validator, "G10 L2 P1 X1 X1=2 F1 F2\n").unwrap();
+    }
+
+    #[test]
+    fn modal_validator_builder_error() {
+        assert_eq!(
+            error("#1=2\n"),
+            ModalError::BlockBuilderError(CollectorError::Unsupported)
+        );
+    }
+
+    #[test]
+    fn modal_validator_recovery() {
+        let config = Config::default().with_recovery(true);
+        let mut parser = BlockParser::<i32>::new(config);
+        let mut validator: Validator = ModalValidator::new(BlockCollector::default());
+        assert!(parser.try_feed_str("G0 G1\n", &mut validator).is_err());
+        assert!(validator.builder_mut().take_block().is_none());
+        parser.try_feed_str("G1 X1\n", &mut validator).unwrap();
+        let block = validator.into_builder().take_block().unwrap();
+        assert_eq!(block.g_codes(), [Decimal::new(1, 0)]);
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// The keywords of O-words, which implement subroutines and control flow (e.g., `O100 sub`.)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum OWordKeyword {
+    /// `sub`, the start of a subroutine definition.
+    Sub,
+
+    /// `endsub`, the end of a subroutine definition. It may have one argument, the return value.
+    EndSub,
+
+    /// `call`, a call to a subroutine. The arguments are passed to the subroutine.
+    Call,
+
+    /// `return`, an early return from a subroutine. It may have one argument, the return value.
+    Return,
+
+    /// `if`, the start of a conditional. It has one argument, the condition.
+    If,
+
+    /// `elseif`, an alternative of a conditional. It has one argument, the condition.
+    ElseIf,
+
+    /// `else`, the final alternative of a conditional.
+    Else,
+
+    /// `endif`, the end of a conditional.
+    EndIf,
+
+    /// `do`, the start of a loop whose condition follows the body.
+    Do,
+
+    /// `while`, the start of a loop, or the end of a `do` loop. It has one argument, the
+    /// condition.
+    While,
+
+    /// `endwhile`, the end of a `while` loop.
+    EndWhile,
+
+    /// `repeat`, the start of a loop. It has one argument, the number of repetitions.
+    Repeat,
+
+    /// `endrepeat`, the end of a `repeat` loop.
+    EndRepeat,
+
+    /// `break`, an early exit from a loop.
+    Break,
+
+    /// `continue`, an early start of the next iteration of a loop.
+    Continue,
+}
+
+impl OWordKeyword {
+    /// Returns the name of the keyword, in upper case.
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Sub => "SUB",
+            Self::EndSub => "ENDSUB",
+            Self::Call => "CALL",
+            Self::Return => "RETURN",
+            Self::If => "IF",
+            Self::ElseIf => "ELSEIF",
+            Self::Else => "ELSE",
+            Self::EndIf => "ENDIF",
+            Self::Do => "DO",
+            Self::While => "WHILE",
+            Self::EndWhile => "ENDWHILE",
+            Self::Repeat => "REPEAT",
+            Self::EndRepeat => "ENDREPEAT",
+            Self::Break => "BREAK",
+            Self::Continue => "CONTINUE",
+        }
+    }
+
+    pub(crate) fn from_name(name: &[u8]) -> Option<Self> {
+        match name {
+            b"SUB" => Some(Self::Sub),
+            b"ENDSUB" => Some(Self::EndSub),
+            b"CALL" => Some(Self::Call),
+            b"RETURN" => Some(Self::Return),
+            b"IF" => Some(Self::If),
+            b"ELSEIF" => Some(Self::ElseIf),
+            b"ELSE" => Some(Self::Else),
+            b"ENDIF" => Some(Self::EndIf),
+            b"DO" => Some(Self::Do),
+            b"WHILE" => Some(Self::While),
+            b"ENDWHILE" => Some(Self::EndWhile),
+            b"REPEAT" => Some(Self::Repeat),
+            b"ENDREPEAT" => Some(Self::EndRepeat),
+            b"BREAK" => Some(Self::Break),
+            b"CONTINUE" => Some(Self::Continue),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// A position in the input of the [`BlockParser`](crate::BlockParser).
+///
+/// All fields are zero-based.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Position {
+    line: usize,
+    column: usize,
+    offset: usize,
+}
+
+impl Position {
+    /// Returns the line, which is the number of line feed characters (`\n`) that precede the
+    /// position.
+    #[must_use]
+    pub const fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column, which is the number of characters that precede the position on its
+    /// line.
+    #[must_use]
+    pub const fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the offset, which is the number of bytes that precede the position.
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub(crate) const fn new() -> Self {
+        Self {
+            line: 0,
+            column: 0,
+            offset: 0,
+        }
+    }
+
+    /// Advances past a character that was encoded as `len` bytes of input.
+    pub(crate) const fn advance(&mut self, c: char, len: usize) {
+        self.offset = self.offset.saturating_add(len);
+        if '\n' == c {
+            self.line = self.line.saturating_add(1);
+            self.column = 0;
+        } else {
+            self.column = self.column.saturating_add(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance() {
+        let mut position = Position::new();
+        for c in "G1\nX\u{b0}".chars() {
+            position.advance(c, c.len_utf8());
+        }
+        assert_eq!(position.line(), 1);
+        assert_eq!(position.column(), 2);
+        assert_eq!(position.offset(), 6);
+    }
+}
@@ -6,10 +6,14 @@ use crate::sign::Sign;
 
 /// The trait for numeric types that can be used to store the significand of a [`Decimal`] number.
 ///
+/// The trait is implemented for `i8`, `i16`, `i32`, `i64`, `u8`, `u16`, `u32`, and `u64`. It is
+/// sealed, so it cannot be implemented for other types, and methods can be added to it without
+/// breaking changes.
+///
 /// [`Decimal`]: crate::Decimal
 pub trait Significand
 where
-    Self: Clone + Copy + Default,
+    Self: Clone + Copy + Default + Ord + sealed::Sealed,
 {
     /// Returns `true` if the number is zero.
     fn is_zero(&self) -> bool;
@@ -18,6 +22,18 @@ where
     /// `None` if overflow occurred.
     fn checked_shl10(self, exp: u32) -> Option<Self>;
 
+    /// Checked addition. Computes `self + rhs`, returning `None` if overflow occurred.
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    /// Checked subtraction. Computes `self - rhs`, returning `None` if overflow occurred.
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+    /// Checked multiplication. Computes `self × rhs`, returning `None` if overflow occurred.
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+
+    /// Checked negation. Computes `-self`, returning `None` if overflow occurred.
+    fn checked_neg(self) -> Option<Self>;
+
     /// Checked addition with an unsigned integer. Computes `self + rhs`, returning `None` if
     /// overflow occurred.
     fn checked_add_unsigned(self, rhs: u32) -> Option<Self>;
@@ -25,63 +41,135 @@ where
     /// Checked subtraction with an unsigned integer. Computes `self - rhs`, returning `None` if
     /// overflow occurred.
     fn checked_sub_unsigned(self, rhs: u32) -> Option<Self>;
+
+    /// Returns `true` if the number is less than zero.
+    fn is_negative(&self) -> bool;
+
+    /// Division by ten, rounding toward zero. Returns the quotient and the absolute value of the
+    /// remainder.
+    fn div_rem10(self) -> (Self, u8);
+}
+
+mod sealed {
+    pub trait Sealed {}
 }
 
-impl Significand for i32 {
-    fn is_zero(&self) -> bool {
-        0 == *self
-    }
-
-    fn checked_shl10(self, exp: u32) -> Option<Self> {
-        cfg_if::cfg_if! {
-            if #[cfg(not(feature = "mul10_by_shl"))] {
-
-                10_i32.checked_pow(exp)?.checked_mul(self)
-
-            } else {
-
-                // This is SIGNIFICANTLY faster on ARM Cortex-M0. The overall performance of the
-                // G-code parser is around 5% to 40% faster, depending on the density of numeric
-                // data in the file.
-                //
-                // ARM does not set the V (overflow) flag when the MUL instruction overflows (e.g.,
-                // see the [ARMv6-M Architecture Reference Manual][1] A.6.7.44 MUL, which reads
-                // "APSR.V unchanged".) The compiler implements `checked_mul` by using the
-                // `__aeabi_lmul` function to perform a widened 64-bit multiplication. This has
-                // both the overhead of the function call itself (it does not get inlined, even
-                // with LTO) and dozens of arithmetic instructions, most of which end up computing
-                // bits that are ultimately thrown away. It is expensive.
-                //
-                // [1]: https://developer.arm.com/documentation/ddi0419/latest/
-                //
-                // y = x * 10
-                // y = x * (8 + 2)
-                // y = (x * 8) + (x * 2)
-                // y = (x << 3) + (x << 1)
-
-                let mut acc = self;
-                let mut exp = exp;
-                while exp > 0 {
-                    let x8 = acc.checked_shl(3)?;
-                    let x2 = acc.checked_shl(1)?;
-                    acc = x8.checked_add(x2)?;
-                    exp -= 1;
-                };
-                Some(acc)
+macro_rules! impl_significand {
+    ($t:ty, $u:ty, $checked_add:ident, $checked_sub:ident, $sign:ident) => {
+        impl sealed::Sealed for $t {}
 
+        impl Significand for $t {
+            fn is_zero(&self) -> bool {
+                0 == *self
             }
-        }
-    }
 
-    fn checked_add_unsigned(self, rhs: u32) -> Option<Self> {
-        <Self>::checked_add_unsigned(self, rhs)
-    }
+            fn checked_shl10(self, exp: u32) -> Option<Self> {
+                cfg_if::cfg_if! {
+                    if #[cfg(not(feature = "mul10_by_shl"))] {
 
-    fn checked_sub_unsigned(self, rhs: u32) -> Option<Self> {
-        <Self>::checked_sub_unsigned(self, rhs)
-    }
+                        <$t>::checked_pow(10, exp)?.checked_mul(self)
+
+                    } else {
+
+                        // This is SIGNIFICANTLY faster on ARM Cortex-M0. The overall performance
+                        // of the G-code parser is around 5% to 40% faster, depending on the
+                        // density of numeric data in the file.
+                        //
+                        // ARM does not set the V (overflow) flag when the MUL instruction
+                        // overflows (e.g., see the [ARMv6-M Architecture Reference Manual][1]
+                        // A.6.7.44 MUL, which reads "APSR.V unchanged".) The compiler implements
+                        // `checked_mul` by using the `__aeabi_lmul` function to perform a widened
+                        // 64-bit multiplication. This has both the overhead of the function call
+                        // itself (it does not get inlined, even with LTO) and dozens of
+                        // arithmetic instructions, most of which end up computing bits that are
+                        // ultimately thrown away. It is expensive. (For i64, the multiplication
+                        // is even wider.)
+                        //
+                        // [1]: https://developer.arm.com/documentation/ddi0419/latest/
+                        //
+                        // y = x * 10
+                        // y = x * (8 + 2)
+                        // y = (x * 8) + (x * 2)
+                        // y = (x << 3) + (x << 1)
+                        //
+                        // `checked_shl` only checks the shift amount, not the bits that are
+                        // shifted out, so each shift is a checked doubling instead. ARM sets the
+                        // V flag for ADDS.
+
+                        let mut acc = self;
+                        let mut exp = exp;
+                        while exp > 0 {
+                            let x2 = acc.checked_add(acc)?;
+                            let x4 = x2.checked_add(x2)?;
+                            let x8 = x4.checked_add(x4)?;
+                            acc = x8.checked_add(x2)?;
+                            exp -= 1;
+                        };
+                        Some(acc)
+
+                    }
+                }
+            }
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn checked_neg(self) -> Option<Self> {
+                <$t>::checked_neg(self)
+            }
+
+            fn checked_add_unsigned(self, rhs: u32) -> Option<Self> {
+                <$t>::$checked_add(self, <$u>::try_from(rhs).ok()?)
+            }
+
+            fn checked_sub_unsigned(self, rhs: u32) -> Option<Self> {
+                <$t>::$checked_sub(self, <$u>::try_from(rhs).ok()?)
+            }
+
+            fn is_negative(&self) -> bool {
+                impl_significand!(@is_negative $sign, *self)
+            }
+
+            fn div_rem10(self) -> (Self, u8) {
+                let remainder = impl_significand!(@abs $sign, self % 10);
+                // The `as` conversion cannot truncate a remainder of a division by ten.
+                #[allow(clippy::cast_possible_truncation)]
+                (self / 10, remainder as u8)
+            }
+        }
+    };
+    (@is_negative signed, $x:expr) => {
+        $x.is_negative()
+    };
+    (@is_negative unsigned, $x:expr) => {
+        false
+    };
+    (@abs signed, $x:expr) => {
+        $x.unsigned_abs()
+    };
+    (@abs unsigned, $x:expr) => {
+        $x
+    };
 }
 
+impl_significand!(i8, u8, checked_add_unsigned, checked_sub_unsigned, signed);
+impl_significand!(i16, u16, checked_add_unsigned, checked_sub_unsigned, signed);
+impl_significand!(i32, u32, checked_add_unsigned, checked_sub_unsigned, signed);
+impl_significand!(i64, u64, checked_add_unsigned, checked_sub_unsigned, signed);
+impl_significand!(u8, u8, checked_add, checked_sub, unsigned);
+impl_significand!(u16, u16, checked_add, checked_sub, unsigned);
+impl_significand!(u32, u32, checked_add, checked_sub, unsigned);
+impl_significand!(u64, u64, checked_add, checked_sub, unsigned);
+
 pub trait SignificandExt
 where
     Self: Sized,
@@ -99,7 +187,7 @@ where
         // https://github.com/rust-lang/rust/issues/110395
         let zero: u32 = '0'.into();
 
-        let digit = u32::try_from(digit).ok()?.checked_sub(zero)?;
+        let digit = u32::from(digit).checked_sub(zero)?;
         if (0..=9).contains(&digit) {
             let significand = self.checked_shl10(exp)?;
             match sign {
@@ -135,6 +223,12 @@ mod tests {
         assert_eq!(Significand::checked_shl10(6_i32, 4), Some(60000));
     }
 
+    #[test]
+    fn i32_checked_shl10_none() {
+        assert_eq!(Significand::checked_shl10(1_i32 << 29, 1), None);
+        assert_eq!(Significand::checked_shl10(-3_i32, 9), None);
+    }
+
     #[test]
     fn i32_checked_add_unsigned() {
         assert_eq!(Significand::checked_add_unsigned(7_i32, 8), Some(15));
@@ -155,6 +249,246 @@ mod tests {
         assert_eq!(Significand::checked_sub_unsigned(i32::MIN, 1), None);
     }
 
+    #[test]
+    fn i32_checked_arithmetic() {
+        assert_eq!(Significand::checked_add(7_i32, -8), Some(-1));
+        assert_eq!(Significand::checked_sub(7_i32, -8), Some(15));
+        assert_eq!(Significand::checked_mul(7_i32, -8), Some(-56));
+        assert_eq!(Significand::checked_neg(7_i32), Some(-7));
+    }
+
+    #[test]
+    fn i32_checked_arithmetic_none() {
+        assert_eq!(Significand::checked_add(i32::MAX, 1), None);
+        assert_eq!(Significand::checked_sub(i32::MIN, 1), None);
+        assert_eq!(Significand::checked_mul(i32::MAX, 2), None);
+        assert_eq!(Significand::checked_neg(i32::MIN), None);
+    }
+
+    #[test]
+    fn i32_is_negative() {
+        assert!(Significand::is_negative(&-1_i32));
+        assert!(!Significand::is_negative(&0_i32));
+    }
+
+    #[test]
+    fn i32_div_rem10() {
+        assert_eq!(Significand::div_rem10(1_234_i32), (123, 4));
+        assert_eq!(Significand::div_rem10(-1_234_i32), (-123, 4));
+        assert_eq!(Significand::div_rem10(i32::MIN), (-214_748_364, 8));
+    }
+
+    //
+    // Significand for i8
+    //
+
+    #[test]
+    fn i8_checked_shl10() {
+        assert_eq!(Significand::checked_shl10(-1_i8, 2), Some(-100));
+    }
+
+    #[test]
+    fn i8_checked_shl10_none() {
+        assert_eq!(Significand::checked_shl10(13_i8, 1), None);
+        assert_eq!(Significand::checked_shl10(1_i8, 3), None);
+    }
+
+    #[test]
+    fn i8_checked_add_unsigned() {
+        assert_eq!(Significand::checked_add_unsigned(-128_i8, 255), Some(127));
+    }
+
+    #[test]
+    fn i8_checked_add_unsigned_none() {
+        assert_eq!(Significand::checked_add_unsigned(0_i8, 256), None);
+    }
+
+    #[test]
+    fn i8_checked_sub_unsigned_none() {
+        assert_eq!(Significand::checked_sub_unsigned(i8::MIN, 1), None);
+    }
+
+    //
+    // Significand for i16
+    //
+
+    #[test]
+    fn i16_checked_shl10() {
+        assert_eq!(Significand::checked_shl10(3_i16, 4), Some(30000));
+    }
+
+    #[test]
+    fn i16_checked_shl10_none() {
+        assert_eq!(Significand::checked_shl10(1_i16 << 13, 1), None);
+        assert_eq!(Significand::checked_shl10(-4_i16, 4), None);
+    }
+
+    #[test]
+    fn i16_checked_add_unsigned() {
+        assert_eq!(Significand::checked_add_unsigned(-9_i16, 8), Some(-1));
+    }
+
+    #[test]
+    fn i16_checked_add_unsigned_none() {
+        assert_eq!(Significand::checked_add_unsigned(i16::MAX, 1), None);
+    }
+
+    #[test]
+    fn i16_checked_sub_unsigned_none() {
+        assert_eq!(Significand::checked_sub_unsigned(0_i16, 65536), None);
+    }
+
+    //
+    // Significand for i64
+    //
+
+    #[test]
+    fn i64_is_zero_true() {
+        assert!(0_i64.is_zero());
+    }
+
+    #[test]
+    fn i64_is_zero_false() {
+        assert!(!9_i64.is_zero());
+    }
+
+    #[test]
+    fn i64_checked_shl10() {
+        assert_eq!(
+            Significand::checked_shl10(6_i64, 12),
+            Some(6_000_000_000_000)
+        );
+    }
+
+    #[test]
+    fn i64_checked_shl10_none() {
+        assert_eq!(Significand::checked_shl10(1_i64 << 61, 1), None);
+        assert_eq!(Significand::checked_shl10(-10_i64, 18), None);
+    }
+
+    #[test]
+    fn i64_checked_add_unsigned() {
+        assert_eq!(
+            Significand::checked_add_unsigned(7_i64, u32::MAX),
+            Some(4_294_967_302)
+        );
+    }
+
+    #[test]
+    fn i64_checked_add_unsigned_none() {
+        assert_eq!(Significand::checked_add_unsigned(i64::MAX, 1), None);
+    }
+
+    #[test]
+    fn i64_checked_sub_unsigned() {
+        assert_eq!(Significand::checked_sub_unsigned(-9_i64, 3), Some(-12));
+    }
+
+    #[test]
+    fn i64_checked_sub_unsigned_none() {
+        assert_eq!(Significand::checked_sub_unsigned(i64::MIN, 1), None);
+    }
+
+    //
+    // Significand for u32
+    //
+
+    #[test]
+    fn u32_checked_neg() {
+        assert_eq!(Significand::checked_neg(0_u32), Some(0));
+        assert_eq!(Significand::checked_neg(1_u32), None);
+    }
+
+    #[test]
+    fn u32_is_negative() {
+        assert!(!Significand::is_negative(&0_u32));
+        assert!(!Significand::is_negative(&u32::MAX));
+    }
+
+    #[test]
+    fn u32_div_rem10() {
+        assert_eq!(Significand::div_rem10(u32::MAX), (429_496_729, 5));
+    }
+
+    #[test]
+    fn u32_checked_shl10() {
+        assert_eq!(Significand::checked_shl10(4_u32, 9), Some(4_000_000_000));
+    }
+
+    #[test]
+    fn u32_checked_shl10_none() {
+        assert_eq!(Significand::checked_shl10(5_u32, 9), None);
+    }
+
+    #[test]
+    fn u32_checked_add_unsigned() {
+        assert_eq!(
+            Significand::checked_add_unsigned(u32::MAX - 1, 1),
+            Some(u32::MAX)
+        );
+    }
+
+    #[test]
+    fn u32_checked_add_unsigned_none() {
+        assert_eq!(Significand::checked_add_unsigned(u32::MAX, 1), None);
+    }
+
+    #[test]
+    fn u32_checked_sub_unsigned() {
+        assert_eq!(Significand::checked_sub_unsigned(9_u32, 3), Some(6));
+    }
+
+    #[test]
+    fn u32_checked_sub_unsigned_none() {
+        assert_eq!(Significand::checked_sub_unsigned(0_u32, 1), None);
+    }
+
+    //
+    // Significand for u64
+    //
+
+    #[test]
+    fn u64_checked_shl10() {
+        assert_eq!(
+            Significand::checked_shl10(1_u64, 19),
+            Some(10_000_000_000_000_000_000)
+        );
+    }
+
+    #[test]
+    fn u64_checked_shl10_none() {
+        assert_eq!(Significand::checked_shl10(2_u64, 19), None);
+    }
+
+    #[test]
+    fn u64_checked_sub_unsigned_none() {
+        assert_eq!(Significand::checked_sub_unsigned(0_u64, 1), None);
+    }
+
+    //
+    // Significand for u8 and u16
+    //
+
+    #[test]
+    fn u8_checked_shl10_none() {
+        assert_eq!(Significand::checked_shl10(3_u8, 2), None);
+    }
+
+    #[test]
+    fn u8_checked_add_unsigned_none() {
+        assert_eq!(Significand::checked_add_unsigned(0_u8, 256), None);
+    }
+
+    #[test]
+    fn u16_checked_shl10() {
+        assert_eq!(Significand::checked_shl10(6_u16, 4), Some(60000));
+    }
+
+    #[test]
+    fn u16_checked_sub_unsigned_none() {
+        assert_eq!(Significand::checked_sub_unsigned(0_u16, 1), None);
+    }
+
     //
     // SignificandExt for i32
     //
@@ -171,6 +505,12 @@ mod tests {
         assert_eq!(left, Some(809));
     }
 
+    #[test]
+    fn checked_append_digit_unsigned_negative() {
+        assert_eq!(0_u32.checked_append_digit(1, '0', Sign::Negative), Some(0));
+        assert_eq!(0_u32.checked_append_digit(1, '1', Sign::Negative), None);
+    }
+
     #[test]
     fn checked_append_digit_negative() {
         let left = (-2).checked_append_digit(3, '5', Sign::Negative);
@@ -0,0 +1,104 @@
+//! Fixtures for the tests of the [`Interpreter`] and of the [`Machine`] implementations.
+
+use core::fmt::Debug;
+
+use crate::block::{BlockParser, Error, ErrorKind};
+use crate::decimal::Decimal;
+use crate::interpreter::{Interpreter, InterpreterError, MachineState, SpindleDirection};
+use crate::machine::{Arc, Machine, Point};
+
+extern crate std;
+use std::vec::Vec;
+
+/// A call to a [`Machine`].
+#[derive(Debug, Eq, PartialEq)]
+pub enum Move {
+    Rapid(Point<i32>),
+    Linear(Point<i32>, Decimal<i32>),
+    Arc(Arc<i32>, Decimal<i32>),
+    Dwell(Decimal<i32>),
+    Tool(Decimal<i32>),
+    MCode(Decimal<i32>),
+    ModalState(SpindleDirection),
+}
+
+/// Records every call.
+impl Machine for Vec<Move> {
+    type Error = ();
+    type Significand = i32;
+
+    fn rapid(&mut self, end: Point<i32>) -> Result<(), Self::Error> {
+        self.push(Move::Rapid(end));
+        Ok(())
+    }
+
+    fn linear(&mut self, end: Point<i32>, feed_rate: Decimal<i32>) -> Result<(), Self::Error> {
+        self.push(Move::Linear(end, feed_rate));
+        Ok(())
+    }
+
+    fn arc(&mut self, arc: Arc<i32>, feed_rate: Decimal<i32>) -> Result<(), Self::Error> {
+        self.push(Move::Arc(arc, feed_rate));
+        Ok(())
+    }
+
+    fn dwell(&mut self, seconds: Decimal<i32>) -> Result<(), Self::Error> {
+        self.push(Move::Dwell(seconds));
+        Ok(())
+    }
+
+    fn select_tool(&mut self, tool: Decimal<i32>) -> Result<(), Self::Error> {
+        self.push(Move::Tool(tool));
+        Ok(())
+    }
+
+    fn m_code(&mut self, number: Decimal<i32>) -> Result<(), Self::Error> {
+        self.push(Move::MCode(number));
+        Ok(())
+    }
+
+    fn modal_state(&mut self, state: &MachineState<i32>) -> Result<(), Self::Error> {
+        self.push(Move::ModalState(state.spindle()));
+        Ok(())
+    }
+}
+
+pub fn point(x: i32, y: i32, z: i32) -> Point<i32> {
+    Point::new(Decimal::new(x, 0), Decimal::new(y, 0), Decimal::new(z, 0))
+}
+
+/// Interprets `s` with `machine`.
+pub fn interpret<M>(machine: M, s: &str) -> Interpreter<M>
+where
+    M: Machine<Significand = i32>,
+    M::Error: Debug,
+{
+    let mut interpreter = Interpreter::new(machine);
+    BlockParser::<i32>::default()
+        .try_feed_str(s, &mut interpreter)
+        .unwrap();
+    interpreter
+}
+
+/// Returns the calls to the machine, except [`Machine::modal_state`].
+pub fn moves(s: &str) -> Vec<Move> {
+    interpret(Vec::new(), s)
+        .into_machine()
+        .into_iter()
+        .filter(|m| !matches!(m, Move::ModalState(_)))
+        .collect()
+}
+
+/// Returns the error of the [`Interpreter`] for `s`.
+pub fn error<M>(machine: M, s: &str) -> InterpreterError<M::Error>
+where
+    M: Machine<Significand = i32>,
+    M::Error: Copy + Debug,
+{
+    let mut interpreter = Interpreter::new(machine);
+    let result = BlockParser::<i32>::default().try_feed_str(s, &mut interpreter);
+    match result.as_ref().map_err(Error::kind) {
+        Err(ErrorKind::BlockBuilderError(error)) => *error,
+        result => panic!("{result:?}"),
+    }
+}
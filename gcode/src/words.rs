@@ -0,0 +1,100 @@
+use crate::significand::Significand;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// The number of indexes that are tracked for each address.
+const INDEXES: u8 = 32;
+
+/// A fixed-size set of the words of a block, used to detect duplicate words.
+///
+/// Addresses are case-insensitive. Only indexes 0 to 31 are tracked. Words with larger indexes
+/// cannot be inserted.
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct WordSet {
+    addresses: u32,
+    indexes: [u32; 26],
+}
+
+impl WordSet {
+    /// Inserts a word. Returns `Some(false)` if the word was already present, or `None` if its
+    /// index is not tracked. Words whose address is not a letter are not tracked and are always
+    /// inserted.
+    pub fn try_insert<I>(&mut self, address: char, index: Option<I>) -> Option<bool>
+    where
+        I: Significand,
+    {
+        let Some(letter) = letter(address) else {
+            return Some(true);
+        };
+        let (set, bit) = match index {
+            None => (&mut self.addresses, letter),
+            Some(index) => (&mut self.indexes[usize::from(letter)], small_index(index)?),
+        };
+        let mask = 1 << bit;
+        let inserted = 0 == *set & mask;
+        *set |= mask;
+        Some(inserted)
+    }
+
+    /// Removes all words.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Returns the position of a letter in the alphabet, in either case.
+fn letter(address: char) -> Option<u8> {
+    let address = u8::try_from(address.to_ascii_uppercase()).ok()?;
+    address.is_ascii_uppercase().then(|| address - b'A')
+}
+
+/// Returns the index if it is tracked.
+fn small_index<I>(index: I) -> Option<u8>
+where
+    I: Significand,
+{
+    if index.is_negative() {
+        return None;
+    }
+    let (tens, ones) = index.div_rem10();
+    let (hundreds, tens) = tens.div_rem10();
+    let index = tens * 10 + ones;
+    (hundreds.is_zero() && index < INDEXES).then_some(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_insert() {
+        let mut words = WordSet::default();
+        assert_eq!(words.try_insert::<u8>('X', None), Some(true));
+        assert_eq!(words.try_insert::<u8>('x', None), Some(false));
+        assert_eq!(words.try_insert('X', Some(1_u8)), Some(true));
+        assert_eq!(words.try_insert('X', Some(2_u8)), Some(true));
+        assert_eq!(words.try_insert('x', Some(1_u8)), Some(false));
+        assert_eq!(words.try_insert('Y', Some(1_u8)), Some(true));
+        words.clear();
+        assert_eq!(words.try_insert::<u8>('X', None), Some(true));
+    }
+
+    #[test]
+    fn try_insert_untracked() {
+        let mut words = WordSet::default();
+        assert_eq!(words.try_insert('X', Some(32_u32)), None);
+        assert_eq!(words.try_insert('X', Some(100_u32)), None);
+        assert_eq!(words.try_insert('X', Some(-1_i32)), None);
+        assert_eq!(words.try_insert::<u8>('#', None), Some(true));
+        assert_eq!(words.try_insert::<u8>('#', None), Some(true));
+    }
+
+    #[test]
+    fn try_insert_index_31() {
+        let mut words = WordSet::default();
+        assert_eq!(words.try_insert('Z', Some(31_i32)), Some(true));
+        assert_eq!(words.try_insert('Z', Some(31_i32)), Some(false));
+    }
+}
@@ -0,0 +1,770 @@
+use core::fmt::{self, Write};
+use core::marker::PhantomData;
+
+use crate::block::{BlockBuilder, CommentKind};
+use crate::buffer::Buffer;
+use crate::decimal::{Decimal, Rounding};
+use crate::digits::Digits;
+use crate::expression::ExpressionToken;
+use crate::o_word::OWordKeyword;
+use crate::significand::Significand;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// The options that select the format of the text written by the [`BlockWriter`].
+///
+/// The default configuration writes upper case letters, separates words with a space character,
+/// does not pad sequence numbers, and writes the shortest text of each number.
+///
+///     use gcode::{Rounding, WriterConfig};
+///
+///     let config = WriterConfig::new()
+///         .with_separator("")
+///         .with_decimals(Some(3), Rounding::HalfEven);
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct WriterConfig {
+    separator: &'static str,
+    sequence_number_width: usize,
+    lower_case: bool,
+    decimals: Option<u32>,
+    rounding: Rounding,
+}
+
+impl Default for WriterConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WriterConfig {
+    /// Creates a new [`WriterConfig`] with the default options.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            separator: " ",
+            sequence_number_width: 0,
+            lower_case: false,
+            decimals: None,
+            rounding: Rounding::HalfEven,
+        }
+    }
+
+    /// Returns the text written between words.
+    #[must_use]
+    pub const fn separator(&self) -> &'static str {
+        self.separator
+    }
+
+    /// Sets the text written between words. The [`BlockParser`] discards whitespace, so an empty
+    /// separator writes the most compact text (e.g., `G1X1Y2`.)
+    ///
+    /// [`BlockParser`]: crate::BlockParser
+    #[must_use]
+    pub const fn with_separator(self, separator: &'static str) -> Self {
+        Self { separator, ..self }
+    }
+
+    /// Returns the minimum number of digits of sequence numbers.
+    #[must_use]
+    pub const fn sequence_number_width(&self) -> usize {
+        self.sequence_number_width
+    }
+
+    /// Sets the minimum number of digits of sequence numbers. Shorter sequence numbers are padded
+    /// with leading zeros (e.g., `N0010`.)
+    #[must_use]
+    pub const fn with_sequence_number_width(self, sequence_number_width: usize) -> Self {
+        Self {
+            sequence_number_width,
+            ..self
+        }
+    }
+
+    /// Returns `true` if letters are written in lower case.
+    #[must_use]
+    pub const fn lower_case(&self) -> bool {
+        self.lower_case
+    }
+
+    /// Writes letters (addresses, keywords, functions, and operators) in lower case or upper
+    /// case. Comments are written as-is.
+    #[must_use]
+    pub const fn with_lower_case(self, lower_case: bool) -> Self {
+        Self { lower_case, ..self }
+    }
+
+    /// Returns the number of digits right of the decimal point of data words and parameter
+    /// assignments, if fixed.
+    #[must_use]
+    pub const fn decimals(&self) -> Option<u32> {
+        self.decimals
+    }
+
+    /// Returns the rounding mode of data words and parameter assignments with fixed decimals.
+    #[must_use]
+    pub const fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    /// Writes data words and parameter assignments with a fixed number of digits right of the
+    /// decimal point, padding with zeros or rounding as necessary. `None` writes the shortest
+    /// text of each number instead.
+    ///
+    /// G codes, M codes, and numbers in expressions are always written with the shortest text.
+    #[must_use]
+    pub const fn with_decimals(self, decimals: Option<u32>, rounding: Rounding) -> Self {
+        Self {
+            decimals,
+            rounding,
+            ..self
+        }
+    }
+}
+
+/// The error type for the [`BlockWriter`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum WriterError {
+    /// The underlying writer returned an error.
+    Write(fmt::Error),
+
+    /// The text of an expression or of the comments is longer than the `CAPACITY` of the
+    /// [`BlockWriter`].
+    Capacity,
+}
+
+impl From<fmt::Error> for WriterError {
+    fn from(error: fmt::Error) -> Self {
+        Self::Write(error)
+    }
+}
+
+/// A [`BlockBuilder`] that writes normalized G-code text.
+///
+/// Together with the [`BlockParser`], it forms a filter that reformats G-code. The text it
+/// writes parses to the same events that produced it, and writing that text again does not change
+/// it.
+///
+/// Expressions are reported before the word or assignment that they belong to, so the text of
+/// each expression is buffered until then. `CAPACITY` is the size of this buffer in bytes.
+///
+/// The [`BlockParser`] reports a comment as soon as it starts, but it reports a word only when the
+/// word ends. A comment is therefore buffered and written after the next word (e.g., `(move) G1
+/// X1` is written as `G1 (move) X1`), and a comment that starts with the `;` character is
+/// buffered until the end of the line. `CAPACITY` is also the size of this buffer in bytes.
+///
+/// If the block has a checksum, the [`BlockWriter`] writes a new checksum of the text that it
+/// wrote.
+///
+/// Everything else is written to the underlying writer as soon as it is reported, so the length
+/// of a line is not limited. If a block is aborted (see [`Config::with_recovery`]) before any of
+/// its words were written, nothing of it is written. Otherwise the words before the error were
+/// already written, and the partial line is ended with a line feed character (`\n`.)
+///
+/// [`BlockParser`]: crate::BlockParser
+/// [`Config::with_recovery`]: crate::Config::with_recovery
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct BlockWriter<W, S, I = S, N = I, const CAPACITY: usize = 64>
+where
+    W: Write,
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    writer: W,
+    config: WriterConfig,
+    line: bool,
+    checksum: u8,
+    comment: Option<CommentKind>,
+    comments: Option<Buffer<CAPACITY>>,
+    semicolon_comment: bool,
+    expression: Option<Buffer<CAPACITY>>,
+    significands: PhantomData<(S, I, N)>,
+}
+
+impl<W, S, I, N, const CAPACITY: usize> BlockWriter<W, S, I, N, CAPACITY>
+where
+    W: Write,
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    /// Creates a new [`BlockWriter`] that writes to `writer`.
+    pub const fn new(writer: W, config: WriterConfig) -> Self {
+        Self {
+            writer,
+            config,
+            line: false,
+            checksum: 0,
+            comment: None,
+            comments: None,
+            semicolon_comment: false,
+            expression: None,
+            significands: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub const fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    fn write_str(&mut self, s: &str) -> Result<(), WriterError> {
+        if let Some(expression) = &mut self.expression {
+            expression
+                .write_str(s)
+                .map_err(|fmt::Error| WriterError::Capacity)
+        } else if let (Some(_), Some(comments)) = (self.comment, &mut self.comments) {
+            comments
+                .write_str(s)
+                .map_err(|fmt::Error| WriterError::Capacity)
+        } else {
+            self.checksum = s.bytes().fold(self.checksum, |checksum, b| checksum ^ b);
+            Ok(self.writer.write_str(s)?)
+        }
+    }
+
+    fn write_char(&mut self, c: char) -> Result<(), WriterError> {
+        self.write_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    fn write_letters(&mut self, s: &str) -> Result<(), WriterError> {
+        for c in s.chars() {
+            let c = if self.config.lower_case {
+                c.to_ascii_lowercase()
+            } else {
+                c.to_ascii_uppercase()
+            };
+            self.write_char(c)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the separator, if the word is not the first of the line.
+    fn start_word(&mut self) -> Result<(), WriterError> {
+        if self.line {
+            self.write_str(self.config.separator)?;
+        }
+        self.line = true;
+        Ok(())
+    }
+
+    fn start_address(&mut self, address: char, index: Option<I>) -> Result<(), WriterError> {
+        self.start_word()?;
+        self.write_letters(address.encode_utf8(&mut [0; 4]))?;
+        if let Some(index) = index {
+            self.write_integer(index)?;
+            self.write_str("=")?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered comments after a word, unless they end the line.
+    fn end_word(&mut self) -> Result<(), WriterError> {
+        if self.semicolon_comment {
+            Ok(())
+        } else {
+            self.write_comments()
+        }
+    }
+
+    fn write_comments(&mut self) -> Result<(), WriterError> {
+        self.semicolon_comment = false;
+        if let Some(comments) = self.comments.take() {
+            self.start_word()?;
+            self.write_str(comments.as_str())?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered comments, if any, and ends the line with a line feed character (`\n`)
+    /// if anything was written.
+    fn finish_line(&mut self) -> Result<(), WriterError> {
+        self.expression = None;
+        self.comment = None;
+        self.write_comments()?;
+        self.end_line_feed()
+    }
+
+    /// Ends the line with a line feed character (`\n`) if anything was written, and discards the
+    /// buffered comments.
+    fn end_line_feed(&mut self) -> Result<(), WriterError> {
+        let line = self.line;
+        self.reset_line();
+        if line {
+            self.writer.write_str("\n")?;
+        }
+        Ok(())
+    }
+
+    /// Discards the state of the line, including the buffered comments.
+    const fn reset_line(&mut self) {
+        self.line = false;
+        self.checksum = 0;
+        self.expression = None;
+        self.comment = None;
+        self.comments = None;
+        self.semicolon_comment = false;
+    }
+
+    fn write_number<T>(&mut self, number: Decimal<T>) -> Result<(), WriterError>
+    where
+        T: Significand,
+    {
+        number.write_minimal(|s| self.write_str(s))
+    }
+
+    fn write_value(&mut self, number: Decimal<S>) -> Result<(), WriterError> {
+        if let Some(decimals) = self.config.decimals {
+            number
+                .fixed(decimals, self.config.rounding)
+                .write(|s| self.write_str(s))
+        } else {
+            self.write_number(number)
+        }
+    }
+
+    fn write_integer<T>(&mut self, value: T) -> Result<(), WriterError>
+    where
+        T: Significand,
+    {
+        self.write_number(Decimal::new(value, 0))
+    }
+
+    fn write_parameter(&mut self, parameter: I) -> Result<(), WriterError> {
+        self.write_str("#")?;
+        self.write_integer(parameter)
+    }
+
+    /// Writes the buffered text of the preceding expression.
+    fn write_expression(&mut self, text: Option<Buffer<CAPACITY>>) -> Result<(), WriterError> {
+        self.write_str(text.unwrap_or_default().as_str())
+    }
+}
+
+impl<W, S, I, N, const CAPACITY: usize> BlockBuilder for BlockWriter<W, S, I, N, CAPACITY>
+where
+    W: Write,
+    S: Significand,
+    I: Significand,
+    N: Significand,
+{
+    type Error = WriterError;
+    type Significand = S;
+    type Index = I;
+    type SequenceNumber = N;
+
+    fn program_start(&mut self) -> Result<(), Self::Error> {
+        self.start_word()?;
+        self.write_str("%")
+    }
+
+    fn sequence_number(
+        &mut self,
+        alignment: bool,
+        number: Decimal<Self::SequenceNumber>,
+    ) -> Result<(), Self::Error> {
+        self.start_word()?;
+        if alignment {
+            self.write_str(":")?;
+        } else {
+            self.write_letters("N")?;
+        }
+        if 0 == number.negative_exponent() {
+            // The sign is written before the padding (e.g., `N-005`.)
+            if number.significand().is_negative() {
+                self.write_str("-")?;
+            }
+            let digits = Digits::new(number.significand());
+            let width = self.config.sequence_number_width;
+            for _ in digits.as_str().len()..width {
+                self.write_str("0")?;
+            }
+            self.write_str(digits.as_str())?;
+        } else {
+            self.write_number(number)?;
+        }
+        self.end_word()
+    }
+
+    fn g_code(&mut self, number: Decimal<Self::Significand>) -> Result<(), Self::Error> {
+        self.start_word()?;
+        self.write_letters("G")?;
+        self.write_number(number)?;
+        self.end_word()
+    }
+
+    fn m_code(&mut self, number: Decimal<Self::Significand>) -> Result<(), Self::Error> {
+        self.start_word()?;
+        self.write_letters("M")?;
+        self.write_number(number)?;
+        self.end_word()
+    }
+
+    fn data(
+        &mut self,
+        address: char,
+        index: Option<Self::Index>,
+        number: Decimal<Self::Significand>,
+    ) -> Result<(), Self::Error> {
+        self.start_address(address, index)?;
+        self.write_value(number)?;
+        self.end_word()
+    }
+
+    fn parameter_word(
+        &mut self,
+        address: char,
+        index: Option<Self::Index>,
+        parameter: Self::Index,
+    ) -> Result<(), Self::Error> {
+        self.start_address(address, index)?;
+        self.write_parameter(parameter)?;
+        self.end_word()
+    }
+
+    fn parameter_assignment(
+        &mut self,
+        parameter: Self::Index,
+        number: Decimal<Self::Significand>,
+    ) -> Result<(), Self::Error> {
+        self.start_word()?;
+        self.write_parameter(parameter)?;
+        self.write_str("=")?;
+        self.write_value(number)?;
+        self.end_word()
+    }
+
+    fn expression(
+        &mut self,
+        token: ExpressionToken<Self::Significand, Self::Index>,
+    ) -> Result<(), Self::Error> {
+        if self.expression.is_none() {
+            self.expression = Some(Buffer::default());
+        }
+        match token {
+            ExpressionToken::Open => self.write_str("["),
+            ExpressionToken::Close => self.write_str("]"),
+            ExpressionToken::Number(number) => self.write_number(number),
+            ExpressionToken::Parameter(parameter) => self.write_parameter(parameter),
+            ExpressionToken::Operator(operator) => {
+                self.write_str(" ")?;
+                self.write_letters(operator.name())?;
+                self.write_str(" ")
+            }
+            ExpressionToken::Negate => self.write_str("-"),
+            ExpressionToken::Function(function) => self.write_letters(function.name()),
+            ExpressionToken::ApplyOperator(_)
+            | ExpressionToken::ApplyNegate
+            | ExpressionToken::ApplyFunction(_) => Ok(()),
+        }
+    }
+
+    fn expression_word(
+        &mut self,
+        address: char,
+        index: Option<Self::Index>,
+    ) -> Result<(), Self::Error> {
+        let text = self.expression.take();
+        self.start_address(address, index)?;
+        self.write_expression(text)?;
+        self.end_word()
+    }
+
+    fn expression_assignment(&mut self, parameter: Self::Index) -> Result<(), Self::Error> {
+        let text = self.expression.take();
+        self.start_word()?;
+        self.write_parameter(parameter)?;
+        self.write_str("=")?;
+        self.write_expression(text)?;
+        self.end_word()
+    }
+
+    fn o_word(&mut self, number: Self::Index, keyword: OWordKeyword) -> Result<(), Self::Error> {
+        self.start_word()?;
+        self.write_letters("O")?;
+        self.write_integer(number)?;
+        self.start_word()?;
+        self.write_letters(keyword.name())?;
+        self.end_word()
+    }
+
+    fn o_word_argument(&mut self) -> Result<(), Self::Error> {
+        let text = self.expression.take();
+        self.start_word()?;
+        self.write_expression(text)?;
+        self.end_word()
+    }
+
+    fn checksum(&mut self, checksum: u8, valid: bool) -> Result<(), Self::Error> {
+        let _ = (checksum, valid);
+        self.write_comments()?;
+        let checksum = self.checksum;
+        self.write_str("*")?;
+        self.write_integer(checksum)
+    }
+
+    fn end_block(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn abort_block(&mut self) -> Result<(), Self::Error> {
+        self.end_line_feed()
+    }
+
+    fn comment_start(&mut self, kind: CommentKind) -> Result<(), Self::Error> {
+        // A comment inside an expression is written with the expression.
+        if self.expression.is_none() {
+            if let Some(comments) = &mut self.comments {
+                comments
+                    .write_str(self.config.separator)
+                    .map_err(|fmt::Error| WriterError::Capacity)?;
+            } else {
+                self.comments = Some(Buffer::default());
+            }
+        }
+        self.comment = Some(kind);
+        match kind {
+            CommentKind::ControlOut => self.write_str("("),
+            CommentKind::Semicolon => {
+                self.semicolon_comment = true;
+                self.write_str(";")
+            }
+        }
+    }
+
+    fn comment_char(&mut self, c: char) -> Result<(), Self::Error> {
+        self.write_char(c)
+    }
+
+    fn comment_end(&mut self) -> Result<(), Self::Error> {
+        if Some(CommentKind::ControlOut) == self.comment {
+            self.write_str(")")?;
+        }
+        self.comment = None;
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> Result<(), Self::Error> {
+        self.finish_line()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{BlockParser, Config, Error, ErrorKind};
+
+    type Text = Buffer<256>;
+
+    fn filter(config: Config, writer_config: WriterConfig, s: &str) -> Text {
+        let mut parser = BlockParser::<i32>::new(config);
+        let mut writer = BlockWriter::<_, i32>::new(Text::default(), writer_config);
+        parser.try_feed_str(s, &mut writer).unwrap();
+        parser.try_end(&mut writer).unwrap();
+        writer.into_writer()
+    }
+
+    fn all() -> Config {
+        Config::new()
+            .with_semicolon_comments(true)
+            .with_parameters(true)
+            .with_expressions(true)
+            .with_o_words(true)
+            .with_checksums(true)
+    }
+
+    #[test]
+    fn default() {
+        assert_eq!(WriterConfig::default(), WriterConfig::new());
+    }
+
+    #[test]
+    fn separator() {
+        let config = WriterConfig::new().with_separator("\t");
+        assert_eq!(config.separator(), "\t");
+        assert_eq!(config.sequence_number_width(), 0);
+    }
+
+    #[test]
+    fn sequence_number_width() {
+        let config = WriterConfig::new().with_sequence_number_width(4);
+        assert_eq!(config.sequence_number_width(), 4);
+        assert!(!config.lower_case());
+    }
+
+    #[test]
+    fn lower_case() {
+        let config = WriterConfig::new().with_lower_case(true);
+        assert!(config.lower_case());
+        assert_eq!(config.decimals(), None);
+    }
+
+    #[test]
+    fn decimals() {
+        let config = WriterConfig::new().with_decimals(Some(3), Rounding::Truncate);
+        assert_eq!(config.decimals(), Some(3));
+        assert_eq!(config.rounding(), Rounding::Truncate);
+        assert_eq!(config.separator(), " ");
+    }
+
+    #[test]
+    fn block_writer() {
+        let text = filter(
+            Config::new(),
+            WriterConfig::new(),
+            "%\nn10 g01x1.50 Y-2  z3=0.5\n:11 G092.10 M3\n",
+        );
+        assert_eq!(text.as_str(), "%\nN10 G1 X1.5 Y-2 Z3=0.5\n:11 G92.1 M3\n");
+    }
+
+    #[test]
+    fn block_writer_config() {
+        let config = WriterConfig::new()
+            .with_separator("")
+            .with_sequence_number_width(4)
+            .with_lower_case(true)
+            .with_decimals(Some(2), Rounding::HalfAwayFromZero);
+        let text = filter(Config::new(), config, "N10 G01 X1.5 Y-2.125 Z3=0.5\n");
+        assert_eq!(text.as_str(), "n0010g1x1.50y-2.13z3=0.50\n");
+    }
+
+    #[test]
+    fn block_writer_negative_sequence_number() {
+        let text = filter(Config::new(), WriterConfig::new(), "N-5 G1 X1\n");
+        assert_eq!(text.as_str(), "N-5 G1 X1\n");
+        let text = filter(Config::new(), WriterConfig::new(), text.as_str());
+        assert_eq!(text.as_str(), "N-5 G1 X1\n");
+        let config = WriterConfig::new().with_sequence_number_width(3);
+        let text = filter(Config::new(), config, "N-5 G1 X1\n");
+        assert_eq!(text.as_str(), "N-005 G1 X1\n");
+    }
+
+    #[test]
+    fn block_writer_comments() {
+        let text = filter(
+            all(),
+            WriterConfig::new(),
+            "(start)\nG01 (move) X1 ; done\n;only\nX[1 (one) + 2]\n",
+        );
+        assert_eq!(
+            text.as_str(),
+            "(start)\nG1 (move) X1 ; done\n;only\nX[(one)1 + 2]\n"
+        );
+    }
+
+    #[test]
+    fn block_writer_parameters() {
+        let text = filter(all(), WriterConfig::new(), "#5=12.5\nG01 X#5 Y2=#6\n");
+        assert_eq!(text.as_str(), "#5=12.5\nG1 X#5 Y2=#6\n");
+    }
+
+    #[test]
+    fn block_writer_expressions() {
+        let text = filter(
+            all(),
+            WriterConfig::new().with_lower_case(true),
+            "G01 X[#1+2.5*COS[30]] Y[ATAN[1]/[2]] Z[1 mod 2 ** 3]\n#5=[-#5-+1]\n",
+        );
+        assert_eq!(
+            text.as_str(),
+            "g1 x[#1 + 2.5 * cos[30]] y[atan[1] / [2]] z[1 mod 2 ** 3]\n#5=[-#5 - 1]\n"
+        );
+    }
+
+    #[test]
+    fn block_writer_o_words() {
+        let text = filter(
+            all(),
+            WriterConfig::new(),
+            "o100 sub\no101 if [#1 gt 2]\no100 call [3] [4]\n",
+        );
+        assert_eq!(
+            text.as_str(),
+            "O100 SUB\nO101 IF [#1 GT 2]\nO100 CALL [3] [4]\n"
+        );
+    }
+
+    #[test]
+    fn block_writer_checksum() {
+        // The checksum of the written text replaces the checksum of the input.
+        let text = filter(all(), WriterConfig::new(), "n123  g01 x1*0\n");
+        assert_eq!(text.as_str(), "N123 G1 X1*97\n");
+    }
+
+    #[test]
+    fn block_writer_round_trip() {
+        let s = "%\nN1 G1 X1.5 Y2=#3 (c) Z[#1 + 2 * SIN[30]]\n#4=[1 / 3]\nO5 CALL [1] [2]*101\n";
+        let text = filter(all(), WriterConfig::new(), s);
+        let again = filter(all(), WriterConfig::new(), text.as_str());
+        assert_eq!(again.as_str(), text.as_str());
+    }
+
+    #[test]
+    fn block_writer_abort() {
+        let config = Config::new().with_recovery(true);
+        let mut parser = BlockParser::<i32>::new(config);
+        let mut writer = BlockWriter::<_, i32>::new(Text::default(), WriterConfig::new());
+        // Nothing of the block was written before the error.
+        let result = parser.try_feed_str("$G01", &mut writer);
+        assert_eq!(
+            result.as_ref().map_err(Error::kind),
+            Err(&ErrorKind::InvalidCharacter)
+        );
+        parser.try_feed_str("\nG02\n", &mut writer).unwrap();
+        assert_eq!(writer.writer().as_str(), "G2\n");
+        // The words before the error were already written, so the partial line is ended.
+        let result = parser.try_feed_str("G01 X1 $", &mut writer);
+        assert_eq!(
+            result.as_ref().map_err(Error::kind),
+            Err(&ErrorKind::InvalidCharacter)
+        );
+        parser.try_feed_str("\nG03\n", &mut writer).unwrap();
+        assert_eq!(writer.writer().as_str(), "G2\nG1 X1\nG3\n");
+    }
+
+    #[test]
+    fn block_writer_capacity() {
+        let mut parser = BlockParser::<i32>::new(all());
+        let mut writer =
+            BlockWriter::<_, i32, i32, i32, 4>::new(Text::default(), WriterConfig::new());
+        let result = parser.try_feed_str("X[1 + 2]\n", &mut writer);
+        assert_eq!(
+            result.as_ref().map_err(Error::kind),
+            Err(&ErrorKind::BlockBuilderError(WriterError::Capacity))
+        );
+    }
+
+    #[test]
+    fn block_writer_long_line() {
+        let s = "N1 G1 X1.5 Y2.5 Z3.5 A4.5 B5.5 C6.5 U7.5 V8.5 W9.5 F100 S2000 (a comment)\n";
+        let mut parser = BlockParser::<i32>::new(Config::new());
+        let mut writer =
+            BlockWriter::<_, i32, i32, i32, 16>::new(Text::default(), WriterConfig::new());
+        parser.try_feed_str(s, &mut writer).unwrap();
+        assert!(s.len() > 16);
+        assert_eq!(writer.writer().as_str(), s);
+    }
+
+    #[test]
+    fn block_writer_write_error() {
+        let mut parser = BlockParser::<i32>::new(Config::new());
+        let mut writer = BlockWriter::<_, i32>::new(Buffer::<4>::default(), WriterConfig::new());
+        let result = parser.try_feed_str("G1 X1 Y2\n", &mut writer);
+        assert_eq!(
+            result.as_ref().map_err(Error::kind),
+            Err(&ErrorKind::BlockBuilderError(WriterError::Write(
+                fmt::Error
+            )))
+        );
+    }
+}